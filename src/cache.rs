@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How a single request interacts with the response cache of the client
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Serve the response from the cache when possible, and store fresh responses
+    #[default]
+    Use,
+    /// Neither read from nor write to the cache
+    Bypass,
+    /// Always fetch a fresh response, and store it in the cache
+    Refresh,
+}
+
+/// In-memory cache of successful responses, keyed by endpoint and normalized query
+///
+/// Each endpoint can be given its own time to live, identified by its path relative to the
+/// base url (for example `characters/profile`).
+#[derive(Debug)]
+pub struct ResponseCache {
+    default_ttl: Duration,
+    ttls: HashMap<String, Duration>,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    body: String,
+    expires: Instant,
+}
+
+impl ResponseCache {
+    /// Cache keeping responses for `default_ttl`, unless the endpoint has its own ttl
+    pub fn new(default_ttl: Duration) -> ResponseCache {
+        ResponseCache {
+            default_ttl,
+            ttls: HashMap::new(),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Keep the responses of the endpoint at `path` for `ttl`
+    pub fn ttl<S: Into<String>>(mut self, path: S, ttl: Duration) -> Self {
+        self.ttls.insert(path.into(), ttl);
+        self
+    }
+
+    /// Remove every cached response
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.expires > Instant::now() => Some(entry.body.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(&self, path: &str, key: String, body: String) {
+        let ttl = self.ttls.get(path).copied().unwrap_or(self.default_ttl);
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.expires > now);
+        entries.insert(
+            key,
+            Entry {
+                body,
+                expires: now + ttl,
+            },
+        );
+    }
+}
+
+/// Identify a request by its path and its query parameters, in a stable order
+pub(crate) fn key(path: &str, query: &str) -> String {
+    let mut pairs: Vec<_> = query.split('&').filter(|p| !p.is_empty()).collect();
+    pairs.sort_unstable();
+    format!("{}?{}", path, pairs.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_key() {
+        assert_eq!(
+            key(
+                "characters/profile",
+                "realm=draenor&name=Andybrew&region=eu"
+            ),
+            key(
+                "characters/profile",
+                "name=Andybrew&region=eu&realm=draenor"
+            ),
+        );
+    }
+
+    #[test]
+    fn expiration() {
+        let cache = ResponseCache::new(Duration::from_secs(60))
+            .ttl("mythic-plus/affixes", Duration::from_secs(0));
+        cache.insert("characters/profile", "a".into(), "{}".into());
+        cache.insert("mythic-plus/affixes", "b".into(), "{}".into());
+        assert_eq!(cache.get("a").as_deref(), Some("{}"));
+        assert_eq!(cache.get("b"), None);
+    }
+}
@@ -0,0 +1,193 @@
+//! Record and replay the traffic of a [`Client`](../struct.Client.html)
+//!
+//! A [`Recorder`](struct.Recorder.html) wraps another transport and writes every request and
+//! its response to a fixture file. A [`Replayer`](struct.Replayer.html) later serves those
+//! responses without any network access, and fails with
+//! [`Error::CassetteMiss`](../enum.Error.html#variant.CassetteMiss) on any request that was not
+//! recorded.
+use crate::transport::{Response, Transport};
+use crate::Error;
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Interaction {
+    url: String,
+    query: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Interaction {
+    fn matches(&self, url: &str, query: &str) -> bool {
+        self.url == url
+            && crate::cache::key("", &self.query)
+                == crate::cache::key("", &without_access_key(query))
+    }
+
+    fn response(&self) -> Result<Response, Error> {
+        let status =
+            StatusCode::from_u16(self.status).map_err(|e| Error::Transport(Box::new(e)))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name: HeaderName = name.parse().map_err(|e| Error::Transport(Box::new(e)))?;
+            let value: HeaderValue = value.parse().map_err(|e| Error::Transport(Box::new(e)))?;
+            headers.append(name, value);
+        }
+        Ok(Response {
+            status,
+            headers,
+            body: self.body.clone(),
+        })
+    }
+}
+
+/// Remove the access key from a query, so that it is never written to a fixture
+fn without_access_key(query: &str) -> String {
+    query
+        .split('&')
+        .filter(|pair| !pair.starts_with("access_key="))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+/// Transport recording the traffic of another transport into a fixture file
+///
+/// The file is rewritten after every request, replacing any previous recording. The access key
+/// of the client is left out of the recorded queries.
+#[derive(Debug)]
+pub struct Recorder<T> {
+    inner: T,
+    path: PathBuf,
+    cassette: tokio::sync::Mutex<Cassette>,
+}
+
+impl<T: Transport> Recorder<T> {
+    pub fn new<P: Into<PathBuf>>(inner: T, path: P) -> Recorder<T> {
+        Recorder {
+            inner,
+            path: path.into(),
+            cassette: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for Recorder<T> {
+    async fn get(&self, url: &str, query: &str) -> Result<Response, Error> {
+        let response = self.inner.get(url, query).await?;
+        let mut cassette = self.cassette.lock().await;
+        cassette.interactions.push(Interaction {
+            url: url.to_owned(),
+            query: without_access_key(query),
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned()))
+                })
+                .collect(),
+            body: response.body.clone(),
+        });
+        let content =
+            serde_json::to_vec_pretty(&*cassette).map_err(|e| Error::Transport(Box::new(e)))?;
+        tokio::fs::write(&self.path, content)
+            .await
+            .map_err(|e| Error::Transport(Box::new(e)))?;
+        Ok(response)
+    }
+}
+
+/// Transport serving the responses of a fixture file written by a
+/// [`Recorder`](struct.Recorder.html)
+///
+/// Identical requests get their recorded responses in order, the last one being repeated once
+/// they have all been served.
+#[derive(Debug)]
+pub struct Replayer {
+    interactions: Vec<Interaction>,
+    served: Vec<AtomicBool>,
+}
+
+impl Replayer {
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Replayer> {
+        let content = std::fs::read(path)?;
+        let cassette: Cassette = serde_json::from_slice(&content)?;
+        Ok(Replayer {
+            served: cassette
+                .interactions
+                .iter()
+                .map(|_| AtomicBool::new(false))
+                .collect(),
+            interactions: cassette.interactions,
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for Replayer {
+    async fn get(&self, url: &str, query: &str) -> Result<Response, Error> {
+        let mut last = None;
+        for (interaction, served) in self.interactions.iter().zip(&self.served) {
+            if interaction.matches(url, query) {
+                if !served.swap(true, Ordering::SeqCst) {
+                    return interaction.response();
+                }
+                last = Some(interaction);
+            }
+        }
+        match last {
+            Some(interaction) => interaction.response(),
+            None => Err(Error::CassetteMiss {
+                url: url.to_owned(),
+                query: without_access_key(query),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryTransport;
+
+    #[tokio::test]
+    async fn record_then_replay() {
+        let path =
+            std::env::temp_dir().join(format!("raider-io-cassette-{}.json", std::process::id()));
+        let recorder = Recorder::new(
+            InMemoryTransport::new().respond(
+                "mythic-plus/affixes",
+                "region=eu",
+                StatusCode::OK,
+                "{}",
+            ),
+            &path,
+        );
+        let url = "https://raider.io/api/v1/mythic-plus/affixes";
+        recorder.get(url, "region=eu").await.unwrap();
+
+        let replayer = Replayer::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let response = replayer.get(url, "region=eu").await.unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body, "{}");
+        assert!(matches!(
+            replayer.get(url, "region=us").await,
+            Err(Error::CassetteMiss { .. })
+        ));
+    }
+}
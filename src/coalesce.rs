@@ -0,0 +1,106 @@
+use crate::Error;
+use futures::channel::oneshot;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+type Waiter = oneshot::Sender<Result<String, Arc<Error>>>;
+
+/// Requests currently being sent, with the callers waiting for their response
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    requests: Mutex<HashMap<String, Vec<Waiter>>>,
+}
+
+enum Role<'a> {
+    Leader(Leader<'a>),
+    Follower(oneshot::Receiver<Result<String, Arc<Error>>>),
+}
+
+/// Caller actually sending a request, removes it from the in-flight requests when dropped so
+/// that its followers are never left waiting
+struct Leader<'a> {
+    in_flight: &'a InFlight,
+    key: &'a str,
+}
+
+impl Leader<'_> {
+    fn finish(self) -> Vec<Waiter> {
+        let waiters = self
+            .in_flight
+            .requests
+            .lock()
+            .unwrap()
+            .remove(self.key)
+            .unwrap_or_default();
+        // The entry is gone, and may already belong to a new leader
+        std::mem::forget(self);
+        waiters
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        self.in_flight.requests.lock().unwrap().remove(self.key);
+    }
+}
+
+impl InFlight {
+    fn join<'a>(&'a self, key: &'a str) -> Role<'a> {
+        let mut requests = self.requests.lock().unwrap();
+        match requests.get_mut(key) {
+            Some(waiters) => {
+                let (sender, receiver) = oneshot::channel();
+                waiters.push(sender);
+                Role::Follower(receiver)
+            }
+            None => {
+                requests.insert(key.to_owned(), Vec::new());
+                Role::Leader(Leader {
+                    in_flight: self,
+                    key,
+                })
+            }
+        }
+    }
+
+    /// Run `request` unless an identical request is already in flight, in which case its
+    /// response is shared
+    ///
+    /// Errors are always wrapped in [`Error::Shared`](../enum.Error.html#variant.Shared), whether
+    /// or not other callers were waiting for the request.
+    pub(crate) async fn run<F, Fut>(&self, key: &str, request: F) -> Result<String, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<String, Error>>,
+    {
+        loop {
+            let leader = match self.join(key) {
+                Role::Follower(receiver) => match receiver.await {
+                    Ok(Ok(body)) => return Ok(body),
+                    Ok(Err(error)) => return Err(Error::Shared(error)),
+                    // The leader was cancelled, try again
+                    Err(oneshot::Canceled) => continue,
+                },
+                Role::Leader(leader) => leader,
+            };
+            let result = request().await;
+            let waiters = leader.finish();
+            return match result {
+                Ok(body) => {
+                    for waiter in waiters {
+                        let _ = waiter.send(Ok(body.clone()));
+                    }
+                    Ok(body)
+                }
+                Err(error) => {
+                    let error = Arc::new(error);
+                    for waiter in waiters {
+                        let _ = waiter.send(Err(error.clone()));
+                    }
+                    Err(Error::Shared(error))
+                }
+            };
+        }
+    }
+}
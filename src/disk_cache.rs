@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Cache of successful responses stored as JSON files under a directory
+///
+/// Responses are grouped in one sub-directory per endpoint. Failing to write an entry does not
+/// fail the request that produced it, the response is simply not cached.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    default_ttl: Duration,
+    ttls: HashMap<String, Duration>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    key: String,
+    stored_at: chrono::DateTime<chrono::Utc>,
+    body: String,
+}
+
+/// Stable hash of a cache key, used as the file name of its entry
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl DiskCache {
+    /// Cache under `dir`, keeping responses for `default_ttl` unless the endpoint has its own
+    /// ttl
+    pub fn new<P: Into<PathBuf>>(dir: P, default_ttl: Duration) -> DiskCache {
+        DiskCache {
+            dir: dir.into(),
+            default_ttl,
+            ttls: HashMap::new(),
+        }
+    }
+    /// Keep the responses of the endpoint at `path` for `ttl`
+    pub fn ttl<S: Into<String>>(mut self, path: S, ttl: Duration) -> Self {
+        self.ttls.insert(path.into(), ttl);
+        self
+    }
+    /// Directory holding the cached responses
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, path: &str, key: &str) -> PathBuf {
+        self.dir
+            .join(path.replace('/', "-"))
+            .join(format!("{:016x}.json", fnv1a(key)))
+    }
+
+    /// Cached body for `key`, ignoring its age when `fresh_only` is false
+    pub(crate) async fn get(&self, path: &str, key: &str, fresh_only: bool) -> Option<String> {
+        let content = tokio::fs::read(self.entry_path(path, key)).await.ok()?;
+        let entry: Entry = serde_json::from_slice(&content).ok()?;
+        if entry.key != key {
+            return None;
+        }
+        if fresh_only {
+            let ttl = self.ttls.get(path).copied().unwrap_or(self.default_ttl);
+            let age = (chrono::Utc::now() - entry.stored_at).to_std().ok()?;
+            if age > ttl {
+                return None;
+            }
+        }
+        Some(entry.body)
+    }
+
+    pub(crate) async fn insert(&self, path: &str, key: &str, body: &str) {
+        let _ = self.write(path, key, body).await;
+    }
+
+    async fn write(&self, path: &str, key: &str, body: &str) -> std::io::Result<()> {
+        let entry_path = self.entry_path(path, key);
+        let entry = Entry {
+            key: key.to_owned(),
+            stored_at: chrono::Utc::now(),
+            body: body.to_owned(),
+        };
+        let content = serde_json::to_vec(&entry)?;
+        if let Some(parent) = entry_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Write then rename so that concurrent readers never see a partial entry
+        let tmp = entry_path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, content).await?;
+        tokio::fs::rename(tmp, entry_path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("raider-io-disk-cache-{}", std::process::id()));
+        let cache = DiskCache::new(&dir, Duration::from_secs(60))
+            .ttl("mythic-plus/affixes", Duration::from_secs(0));
+        cache.insert("characters/profile", "key", "{}").await;
+        cache.insert("mythic-plus/affixes", "key", "[]").await;
+        assert_eq!(
+            cache
+                .get("characters/profile", "key", true)
+                .await
+                .as_deref(),
+            Some("{}")
+        );
+        assert_eq!(cache.get("characters/profile", "other", true).await, None);
+        assert_eq!(cache.get("mythic-plus/affixes", "key", true).await, None);
+        assert_eq!(
+            cache
+                .get("mythic-plus/affixes", "key", false)
+                .await
+                .as_deref(),
+            Some("[]")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,318 @@
+use crate::{player, raid, CacheMode, Client, Error, Region, Request};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Default, Clone)]
+struct GuildDetailsFields {
+    raid_progression: bool,
+    raid_rankings: bool,
+    members: bool,
+}
+impl GuildDetailsFields {
+    fn query(&self) -> Option<String> {
+        let fields = [
+            (self.raid_progression, "raid_progression"),
+            (self.raid_rankings, "raid_rankings"),
+            (self.members, "members"),
+        ]
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, field)| field)
+        .join(",");
+        if fields.is_empty() {
+            None
+        } else {
+            Some(fields)
+        }
+    }
+}
+
+pub struct GuildDetailsRequest<'c> {
+    client: &'c Client,
+    name: &'c str,
+    region: Region,
+    realm: &'c str,
+    fields: GuildDetailsFields,
+    cache_mode: CacheMode,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GuildQuery<'i> {
+    name: &'i str,
+    region: Region,
+    realm: &'i str,
+    fields: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+pub struct DifficultyRankings {
+    pub normal: crate::mythic_plus::Ranking,
+    pub heroic: crate::mythic_plus::Ranking,
+    pub mythic: crate::mythic_plus::Ranking,
+}
+
+/// Character of a guild roster
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct MemberCharacter {
+    pub name: String,
+    pub race: player::Race,
+    pub class: player::Class,
+    pub active_spec_name: player::Spec,
+    pub active_spec_role: player::Role,
+    pub gender: player::Gender,
+    pub faction: player::Faction,
+    pub achievement_points: u64,
+    pub honorable_kills: u64,
+    pub region: Region,
+    pub realm: String,
+    pub last_crawled_at: chrono::DateTime<chrono::Utc>,
+    pub profile_url: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Member {
+    /// Guild rank, 0 being the guild master
+    pub rank: u8,
+    pub character: MemberCharacter,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GuildDetails {
+    pub name: String,
+    pub faction: player::Faction,
+    pub region: Region,
+    pub realm: String,
+    pub last_crawled_at: chrono::DateTime<chrono::Utc>,
+    pub profile_url: String,
+    pub raid_progression: Option<raid::RaidProgression>,
+    /// Rankings of the guild, by raid slug
+    pub raid_rankings: Option<HashMap<String, DifficultyRankings>>,
+    pub members: Option<Vec<Member>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Kill {
+    pub pulled_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub defeated_at: chrono::DateTime<chrono::Utc>,
+    pub duration_ms: Option<u64>,
+    pub item_level_equipped_avg: f32,
+    pub item_level_equipped_max: f32,
+    pub item_level_equipped_min: f32,
+}
+
+/// Character present for a kill
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct KillRosterEntry {
+    pub character: player::RosterCharacter,
+    #[serde(rename = "itemLevelEquipped")]
+    pub item_level_equipped: f32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BossKill {
+    pub kill: Kill,
+    pub roster: Vec<KillRosterEntry>,
+}
+
+impl Client {
+    /// Details of the first kill of a boss by a guild
+    ///
+    /// The raid and the boss are identified by their slugs, for example `nyalotha-the-waking-city`
+    /// and `nzoth-the-corruptor`.
+    pub fn guild_boss_kill(
+        &self,
+        region: Region,
+        realm: &str,
+        guild: &str,
+        raid: &str,
+        boss: &str,
+        difficulty: raid::Difficulty,
+    ) -> Request<'_, BossKill> {
+        Request::new(
+            self,
+            "guilds/boss-kill",
+            vec![
+                ("region", region.text().to_owned()),
+                ("realm", realm.to_owned()),
+                ("guild", guild.to_owned()),
+                ("raid", raid.to_owned()),
+                ("boss", boss.to_owned()),
+                ("difficulty", difficulty.text().to_owned()),
+            ],
+        )
+    }
+    /// Builder for getting guild information
+    pub fn guild_details<'s, 'i: 's>(
+        &'s self,
+        region: Region,
+        name: &'i str,
+        realm: &'i str,
+    ) -> GuildDetailsRequest<'s> {
+        GuildDetailsRequest {
+            client: self,
+            name,
+            region,
+            realm,
+            fields: GuildDetailsFields::default(),
+            cache_mode: CacheMode::default(),
+        }
+    }
+}
+
+impl<'c> GuildDetailsRequest<'c> {
+    /// Clear all the fields from the request
+    pub fn clear(mut self) -> Self {
+        self.fields = Default::default();
+        self
+    }
+    /// retrieve raid progression data for the guild
+    pub fn raid_progression(mut self) -> Self {
+        self.fields.raid_progression = true;
+        self
+    }
+    /// retrieve raid rankings data for the guild
+    pub fn raid_rankings(mut self) -> Self {
+        self.fields.raid_rankings = true;
+        self
+    }
+    /// retrieve the guild's list of members
+    pub fn members(mut self) -> Self {
+        self.fields.members = true;
+        self
+    }
+    /// Choose how this request uses the response cache of the client
+    pub fn cache_mode(mut self, mode: CacheMode) -> Self {
+        self.cache_mode = mode;
+        self
+    }
+
+    /// Execute the query on the raider.io website
+    pub async fn get(&self) -> Result<GuildDetails, Error> {
+        let query = GuildQuery {
+            name: self.name,
+            region: self.region,
+            realm: self.realm,
+            fields: self.fields.query(),
+        };
+        self.client
+            .get("guilds/profile", &query, self.cache_mode)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boss_kill() {
+        let kill: BossKill = serde_json::from_str(
+            r#"{
+                "kill": {
+                    "pulledAt": "2020-02-12T20:31:12.000Z",
+                    "defeatedAt": "2020-02-12T20:40:02.000Z",
+                    "durationMs": 530000,
+                    "isSuccess": true,
+                    "itemLevelEquippedAvg": 475.2,
+                    "itemLevelEquippedMax": 482,
+                    "itemLevelEquippedMin": 466
+                },
+                "roster": [{
+                    "character": {
+                        "id": 1,
+                        "name": "Andybrew",
+                        "faction": "horde",
+                        "class": {"id": 10, "name": "Monk", "slug": "monk"},
+                        "spec": {"id": 268, "name": "Brewmaster", "slug": "brewmaster"}
+                    },
+                    "itemLevelEquipped": 478.5
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(kill.roster.len(), 1);
+        assert!(matches!(
+            kill.roster[0].character.spec,
+            player::Spec::Brewmaster
+        ));
+    }
+
+    #[tokio::test]
+    async fn guild_profile() {
+        let ranking = r#"{"world": 3, "region": 1, "realm": 1}"#;
+        let body = format!(
+            r#"{{
+                "name": "Ensemble",
+                "faction": "alliance",
+                "region": "eu",
+                "realm": "Draenor",
+                "last_crawled_at": "2024-11-20T08:12:40.000Z",
+                "profile_url": "https://raider.io/guilds/eu/draenor/Ensemble",
+                "raid_progression": {{
+                    "nerubar-palace": {{
+                        "summary": "8/8 M",
+                        "total_bosses": 8,
+                        "normal_bosses_killed": 8,
+                        "heroic_bosses_killed": 8,
+                        "mythic_bosses_killed": 8
+                    }}
+                }},
+                "raid_rankings": {{
+                    "nerubar-palace": {{"normal": {0}, "heroic": {0}, "mythic": {0}}}
+                }},
+                "members": [{{
+                    "rank": 0,
+                    "character": {{
+                        "name": "Ellesmere",
+                        "race": "Dracthyr",
+                        "class": "Evoker",
+                        "active_spec_name": "Augmentation",
+                        "active_spec_role": "DPS",
+                        "gender": "female",
+                        "faction": "alliance",
+                        "achievement_points": 31250,
+                        "honorable_kills": 0,
+                        "region": "eu",
+                        "realm": "Draenor",
+                        "last_crawled_at": "2024-11-20T08:12:40.000Z",
+                        "profile_url": "https://raider.io/characters/eu/draenor/Ellesmere"
+                    }}
+                }}]
+            }}"#,
+            ranking
+        );
+        let transport = crate::InMemoryTransport::new().respond(
+            "guilds/profile",
+            "name=Ensemble&region=eu&realm=Draenor&fields=raid_progression%2Craid_rankings%2Cmembers",
+            reqwest::StatusCode::OK,
+            body,
+        );
+        let client = Client::builder().transport(transport).build().unwrap();
+        let guild = client
+            .guild_details(Region::Europe, "Ensemble", "Draenor")
+            .raid_progression()
+            .raid_rankings()
+            .members()
+            .get()
+            .await
+            .unwrap();
+        let progression = guild.raid_progression.unwrap();
+        assert_eq!(
+            progression
+                .get("nerubar-palace")
+                .unwrap()
+                .mythic_bosses_killed,
+            8
+        );
+        assert_eq!(
+            guild.raid_rankings.unwrap()["nerubar-palace"].mythic.world,
+            3
+        );
+        let members = guild.members.unwrap();
+        assert_eq!(members[0].rank, 0);
+        assert!(matches!(members[0].character.race, player::Race::Dracthyr));
+        assert!(matches!(members[0].character.class, player::Class::Evoker));
+    }
+}
@@ -1,27 +1,330 @@
+use futures::{Stream, StreamExt};
 use itertools::Itertools;
-use serde::{Deserialize, Serialize};
+use reqwest::{header::HeaderMap, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
 
 static BASE_URL: &str = "https://raider.io/api/v1";
+/// Environment variable read by
+/// [`ClientBuilder::access_key_from_env`](struct.ClientBuilder.html#method.access_key_from_env)
+pub static ACCESS_KEY_VAR: &str = "RAIDER_IO_ACCESS_KEY";
 
+/// Methods shared by the builders of paginated rankings, holding `region: Option<Region>`,
+/// `max_pages: Option<u32>` and `cache_mode: CacheMode`
+macro_rules! paged_request_setters {
+    () => {
+        /// Only rank the entries of a region, the whole world is ranked by default
+        pub fn region(mut self, region: $crate::Region) -> Self {
+            self.region = Some(region);
+            self
+        }
+        /// Stop after fetching `max_pages` pages
+        pub fn max_pages(mut self, max_pages: u32) -> Self {
+            self.max_pages = Some(max_pages);
+            self
+        }
+        /// Choose how the requests use the response cache of the client
+        pub fn cache_mode(mut self, mode: $crate::CacheMode) -> Self {
+            self.cache_mode = mode;
+            self
+        }
+    };
+}
+
+pub mod cache;
+pub mod cassette;
+mod coalesce;
+pub mod disk_cache;
 pub mod gear;
+pub mod guild;
 pub mod mythic_plus;
 pub mod player;
 pub mod raid;
+pub mod rate_limit;
+pub mod retry;
+pub mod transport;
+
+pub use cache::{CacheMode, ResponseCache};
+pub use disk_cache::DiskCache;
+pub use rate_limit::{Budget, RateLimiter};
+pub use retry::RetryPolicy;
+pub use transport::{InMemoryTransport, ReqwestTransport, Transport};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("http request failed")]
     Http(#[from] reqwest::Error),
+    /// Http error that mentioned the access key of the client, with the key removed
+    #[error("http request failed: {message}")]
+    RedactedHttp { message: String, transient: bool },
+    /// Failure of a custom [`Transport`](transport/trait.Transport.html)
+    #[error("transport failed")]
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("could not encode the query")]
+    Query(#[from] serde_urlencoded::ser::Error),
     #[error("api error: {message}")]
     Api {
         status: u64,
         error: String,
         message: String,
     },
+    #[error("character not found: {message}")]
+    CharacterNotFound { message: String },
+    #[error("realm not found: {message}")]
+    RealmNotFound { message: String },
+    #[error("guild not found: {message}")]
+    GuildNotFound { message: String },
+    #[error("invalid field: {message}")]
+    InvalidField { message: String },
+    /// The API refused the request because too many were made, `retry_after` is the delay
+    /// asked by the server, if any
+    #[error("rate limited by the api")]
+    RateLimited { retry_after: Option<Duration> },
+    /// The server failed to answer, `retry_after` is the delay asked by the server before
+    /// trying again, if any
+    #[error("server error ({status})")]
+    Server {
+        status: u16,
+        body: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("unexpected status code ({status})")]
+    UnexpectedStatus { status: u16, body: String },
+    /// The body of the response could not be parsed as the expected type
+    #[error("unexpected response body ({status})")]
+    UnexpectedBody {
+        status: u16,
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The request failed after being retried, or a transient error could not be retried by
+    /// the [`RetryPolicy`](retry/struct.RetryPolicy.html), `last` is the error of the last
+    /// attempt
+    #[error("request failed after {attempts} attempts")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        last: Box<Error>,
+    },
+    /// The client is offline and the response is not in its disk cache
+    #[error("no cached response for {path}?{query} while offline")]
+    OfflineCacheMiss { path: String, query: String },
+    /// A [`Replayer`](cassette/struct.Replayer.html) has no recording of the request
+    #[error("no recorded response for {url}?{query}")]
+    CassetteMiss { url: String, query: String },
+    /// Error of a coalesced request, see
+    /// [`ClientBuilder::coalesce`](struct.ClientBuilder.html#method.coalesce)
+    #[error(transparent)]
+    Shared(std::sync::Arc<Error>),
+}
+
+impl Error {
+    /// Whether the error may go away by sending the same request again
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Http(e) => e.is_timeout() || e.is_connect(),
+            Error::RedactedHttp { transient, .. } => *transient,
+            Error::RateLimited { .. } | Error::Server { .. } => true,
+            Error::Shared(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+
+    /// Delay asked by the server in a `Retry-After` header before sending the request again
+    pub(crate) fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::RateLimited { retry_after } | Error::Server { retry_after, .. } => *retry_after,
+            Error::Shared(e) => e.retry_after(),
+            _ => None,
+        }
+    }
+
+    /// The error itself, or the one it wraps if it is [`Error::Shared`](#variant.Shared)
+    ///
+    /// Prefer matching on the result of this method, so that the same code handles errors with
+    /// or without [`ClientBuilder::coalesce`](struct.ClientBuilder.html#method.coalesce).
+    pub fn as_inner(&self) -> &Error {
+        match self {
+            Error::Shared(e) => e.as_inner(),
+            error => error,
+        }
+    }
+
+    /// Remove every occurrence of `secret` from the error
+    fn redact(self, secret: &str) -> Error {
+        if secret.is_empty() {
+            return self;
+        }
+        let clean = |text: String| {
+            if text.contains(secret) {
+                text.replace(secret, "<redacted>")
+            } else {
+                text
+            }
+        };
+        match self {
+            Error::Http(e) => {
+                let message = format!("{:?}", e);
+                if message.contains(secret) {
+                    Error::RedactedHttp {
+                        transient: Error::Http(e).is_transient(),
+                        message: clean(message),
+                    }
+                } else {
+                    Error::Http(e)
+                }
+            }
+            Error::Api {
+                status,
+                error,
+                message,
+            } => Error::Api {
+                status,
+                error,
+                message: clean(message),
+            },
+            Error::Server {
+                status,
+                body,
+                retry_after,
+            } => Error::Server {
+                status,
+                body: clean(body),
+                retry_after,
+            },
+            Error::UnexpectedStatus { status, body } => Error::UnexpectedStatus {
+                status,
+                body: clean(body),
+            },
+            Error::UnexpectedBody {
+                status,
+                body,
+                source,
+            } => Error::UnexpectedBody {
+                status,
+                body: clean(body),
+                source,
+            },
+            Error::CassetteMiss { url, query } => Error::CassetteMiss {
+                url: clean(url),
+                query: clean(query),
+            },
+            error => error,
+        }
+    }
+}
+
+/// Key granting higher rate limits to registered applications, never shown in `Debug` output
+#[derive(Clone)]
+struct AccessKey(String);
+
+impl std::fmt::Debug for AccessKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "AccessKey(<redacted>)")
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(error: ApiError) -> Self {
+        let message = error.message.to_lowercase();
+        let not_found = ["could not find", "failed to find", "not found", "unknown"]
+            .iter()
+            .any(|pattern| message.contains(pattern));
+        if not_found && message.contains("character") {
+            Error::CharacterNotFound {
+                message: error.message,
+            }
+        } else if not_found && message.contains("guild") {
+            Error::GuildNotFound {
+                message: error.message,
+            }
+        } else if not_found && message.contains("realm") {
+            Error::RealmNotFound {
+                message: error.message,
+            }
+        } else if message.contains("field") {
+            Error::InvalidField {
+                message: error.message,
+            }
+        } else {
+            Error::Api {
+                status: error.status_code,
+                error: error.error,
+                message: error.message,
+            }
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+/// Parse the delay of a `Retry-After` header, either in seconds or as an http date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+        .or(Some(Duration::from_secs(0)))
+}
+
+/// Turn any unsuccessful response into the matching error, returning the body otherwise
+fn check_response(status: StatusCode, headers: &HeaderMap, body: String) -> Result<String, Error> {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        Err(Error::RateLimited {
+            retry_after: retry_after(headers),
+        })
+    } else if status.is_success() {
+        Ok(body)
+    } else if status.is_client_error() {
+        match serde_json::from_str::<ApiError>(&body) {
+            Ok(error) => Err(error.into()),
+            Err(source) => Err(Error::UnexpectedBody {
+                status: status.as_u16(),
+                body,
+                source,
+            }),
+        }
+    } else if status.is_server_error() {
+        Err(Error::Server {
+            status: status.as_u16(),
+            body,
+            retry_after: retry_after(headers),
+        })
+    } else {
+        Err(Error::UnexpectedStatus {
+            status: status.as_u16(),
+            body,
+        })
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(status: StatusCode, body: String) -> Result<T, Error> {
+    serde_json::from_str(&body).map_err(|source| Error::UnexpectedBody {
+        status: status.as_u16(),
+        body,
+        source,
+    })
+}
+
+#[cfg(test)]
+fn parse_response<T: DeserializeOwned>(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: String,
+) -> Result<T, Error> {
+    deserialize(status, check_response(status, headers, body)?)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Region {
     #[serde(rename = "us")]
     UnitedStates,
@@ -32,29 +335,307 @@ pub enum Region {
     #[serde(rename = "tw")]
     Taiwan,
 }
+impl Region {
+    fn text(&self) -> &'static str {
+        match self {
+            Region::UnitedStates => "us",
+            Region::Europe => "eu",
+            Region::Korea => "kr",
+            Region::Taiwan => "tw",
+        }
+    }
+}
+
+/// Region of the query of the rankings, which cover the whole world without a region
+pub(crate) fn region_or_world(region: Option<Region>) -> &'static str {
+    region.map_or("world", |region| region.text())
+}
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize)]
 pub enum Expansion {
+    Legion,
     BattleForAzeroth,
+    Shadowlands,
+    Dragonflight,
+    TheWarWithin,
 }
 impl Expansion {
     fn text(&self) -> &'static str {
         match self {
+            Expansion::Legion => "legion",
             Expansion::BattleForAzeroth => "bfa",
+            Expansion::Shadowlands => "sl",
+            Expansion::Dragonflight => "df",
+            Expansion::TheWarWithin => "tww",
+        }
+    }
+    fn from_text(text: &str) -> Option<Expansion> {
+        match text {
+            "legion" => Some(Expansion::Legion),
+            "bfa" => Some(Expansion::BattleForAzeroth),
+            "sl" => Some(Expansion::Shadowlands),
+            "df" => Some(Expansion::Dragonflight),
+            "tww" => Some(Expansion::TheWarWithin),
+            _ => None,
+        }
+    }
+    /// Id of the expansion in the static data endpoints
+    pub fn id(&self) -> u8 {
+        match self {
+            Expansion::Legion => 6,
+            Expansion::BattleForAzeroth => 7,
+            Expansion::Shadowlands => 8,
+            Expansion::Dragonflight => 9,
+            Expansion::TheWarWithin => 10,
+        }
+    }
+}
+
+/// Dates given for each region, missing when the event did not happen yet in the region
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+pub struct RegionDates {
+    pub us: Option<chrono::DateTime<chrono::Utc>>,
+    pub eu: Option<chrono::DateTime<chrono::Utc>>,
+    pub tw: Option<chrono::DateTime<chrono::Utc>>,
+    pub kr: Option<chrono::DateTime<chrono::Utc>>,
+}
+impl RegionDates {
+    pub fn get(&self, region: Region) -> Option<chrono::DateTime<chrono::Utc>> {
+        match region {
+            Region::UnitedStates => self.us,
+            Region::Europe => self.eu,
+            Region::Taiwan => self.tw,
+            Region::Korea => self.kr,
         }
     }
 }
 
+/// Request to an endpoint taking no other option than its parameters
+pub struct Request<'c, T> {
+    client: &'c Client,
+    path: &'static str,
+    query: Vec<(&'static str, String)>,
+    cache_mode: CacheMode,
+    response: std::marker::PhantomData<fn() -> T>,
+}
+impl<'c, T: DeserializeOwned> Request<'c, T> {
+    fn new(client: &'c Client, path: &'static str, query: Vec<(&'static str, String)>) -> Self {
+        Request {
+            client,
+            path,
+            query,
+            cache_mode: CacheMode::default(),
+            response: std::marker::PhantomData,
+        }
+    }
+    /// Choose how this request uses the response cache of the client
+    pub fn cache_mode(mut self, mode: CacheMode) -> Self {
+        self.cache_mode = mode;
+        self
+    }
+    /// Execute the query on the raider.io website
+    pub async fn get(&self) -> Result<T, Error> {
+        self.client
+            .get(self.path, &self.query, self.cache_mode)
+            .await
+    }
+}
+
+/// One page of a paginated endpoint
+pub(crate) trait Page: DeserializeOwned {
+    type Item;
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
 /// Client to the raider.io API
+#[derive(Debug)]
 pub struct Client {
-    http_client: reqwest::Client,
+    transport: Box<dyn Transport>,
+    base_url: String,
+    rate_limiter: Option<RateLimiter>,
+    retry: Option<RetryPolicy>,
+    cache: Option<ResponseCache>,
+    disk_cache: Option<DiskCache>,
+    offline: bool,
+    access_key: Option<AccessKey>,
+    in_flight: Option<coalesce::InFlight>,
+}
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 impl Client {
+    /// Client using the default configuration, talking to raider.io
     pub fn new() -> Client {
-        Client {
-            http_client: reqwest::Client::new(),
+        ClientBuilder::new()
+            .build()
+            .expect("could not build the default http client")
+    }
+    /// Builder for a client with a custom configuration
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path)
+    }
+    /// Requests currently allowed by the rate limiter, if the client has one
+    pub fn rate_limit_budget(&self) -> Option<Budget> {
+        self.rate_limiter.as_ref().map(RateLimiter::budget)
+    }
+    /// Remove every response from the cache, if the client has one
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
         }
     }
+    /// Send a GET request to an endpoint of the API, going through the response caches and
+    /// retrying it according to the retry policy of the client
+    ///
+    /// Offline clients only look into the caches, regardless of the cache mode.
+    async fn get<Q, T>(&self, path: &str, query: &Q, cache_mode: CacheMode) -> Result<T, Error>
+    where
+        Q: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let query = serde_urlencoded::to_string(query)?;
+        let key = cache::key(path, &query);
+        let cache_mode = if self.offline {
+            CacheMode::Use
+        } else {
+            cache_mode
+        };
+
+        if cache_mode == CacheMode::Use {
+            if let Some(body) = self.cached(path, &key).await {
+                return deserialize(StatusCode::OK, body);
+            }
+        }
+        if self.offline {
+            return Err(Error::OfflineCacheMiss {
+                path: path.to_owned(),
+                query,
+            });
+        }
+
+        let url = self.endpoint(path);
+        let query = match &self.access_key {
+            Some(AccessKey(key)) => {
+                let access_key = serde_urlencoded::to_string([("access_key", key)])?;
+                if query.is_empty() {
+                    access_key
+                } else {
+                    format!("{}&{}", query, access_key)
+                }
+            }
+            None => query,
+        };
+        let body = match &self.in_flight {
+            Some(in_flight) => {
+                in_flight
+                    .run(&key, || self.get_with_retry(&url, &query))
+                    .await?
+            }
+            None => self.get_with_retry(&url, &query).await?,
+        };
+        if cache_mode == CacheMode::Bypass {
+            return deserialize(StatusCode::OK, body);
+        }
+        let value = deserialize(StatusCode::OK, body.clone())?;
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.insert(path, &key, &body).await;
+        }
+        if let Some(cache) = &self.cache {
+            cache.insert(path, key, body);
+        }
+        Ok(value)
+    }
+    async fn cached(&self, path: &str, key: &str) -> Option<String> {
+        if let Some(body) = self.cache.as_ref().and_then(|cache| cache.get(key)) {
+            return Some(body);
+        }
+        let body = self
+            .disk_cache
+            .as_ref()?
+            .get(path, key, !self.offline)
+            .await?;
+        if let Some(cache) = &self.cache {
+            cache.insert(path, key.to_owned(), body.clone());
+        }
+        Some(body)
+    }
+    async fn get_with_retry(&self, url: &str, query: &str) -> Result<String, Error> {
+        let retry = match &self.retry {
+            None => return self.get_once(url, query).await,
+            Some(retry) => retry,
+        };
+        let mut attempt = 1;
+        loop {
+            let error = match self.get_once(url, query).await {
+                Ok(body) => return Ok(body),
+                Err(error) => error,
+            };
+            match retry.delay(attempt, &error) {
+                Some(delay) => tokio::time::delay_for(delay).await,
+                None if attempt > 1 || error.is_transient() => {
+                    return Err(Error::RetriesExhausted {
+                        attempts: attempt,
+                        last: Box::new(error),
+                    })
+                }
+                None => return Err(error),
+            }
+            attempt += 1;
+        }
+    }
+    async fn get_once(&self, url: &str, query: &str) -> Result<String, Error> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        let result = match self.transport.get(url, query).await {
+            Ok(response) => check_response(response.status, &response.headers, response.body),
+            Err(error) => Err(error),
+        };
+        match &self.access_key {
+            Some(AccessKey(key)) => result.map_err(|error| {
+                let encoded = serde_urlencoded::to_string([("", key)]).unwrap_or_default();
+                error.redact(key).redact(encoded.trim_start_matches('='))
+            }),
+            None => result,
+        }
+    }
+    /// Lazily fetch the pages of a paginated endpoint, starting at page 0, until a page is
+    /// empty, a request fails or `max_pages` have been fetched
+    fn pages<'s, P: Page + 's>(
+        &'s self,
+        path: &'static str,
+        query: Vec<(&'static str, String)>,
+        cache_mode: CacheMode,
+        max_pages: Option<u32>,
+    ) -> impl Stream<Item = Result<P::Item, Error>> + 's {
+        futures::stream::unfold(Some(0), move |page| {
+            let mut query = query.clone();
+            async move {
+                let page = page?;
+                if max_pages.is_some_and(|max_pages| page >= max_pages) {
+                    return None;
+                }
+                query.push(("page", page.to_string()));
+                match self.get::<_, P>(path, &query, cache_mode).await {
+                    Ok(items) => {
+                        let items = items.into_items();
+                        if items.is_empty() {
+                            None
+                        } else {
+                            Some((items.into_iter().map(Ok).collect(), Some(page + 1)))
+                        }
+                    }
+                    Err(error) => Some((vec![Err(error)], None)),
+                }
+            }
+        })
+        .flat_map(futures::stream::iter)
+    }
     /// Builder for getting character information
     pub fn character_details<'s, 'i: 's>(
         &'s self,
@@ -68,10 +649,215 @@ impl Client {
             region,
             realm,
             fields: CharacterDetailsFields::default(),
+            cache_mode: CacheMode::default(),
+        }
+    }
+    /// Builder for getting character information, owning its identity and a handle to the
+    /// client so that it can be stored, cloned and sent to other tasks
+    pub fn owned_character_details<N, R>(
+        self: &Arc<Self>,
+        region: Region,
+        name: N,
+        realm: R,
+    ) -> OwnedCharacterDetailsRequest
+    where
+        N: Into<String>,
+        R: Into<String>,
+    {
+        OwnedCharacterDetailsRequest {
+            client: self.clone(),
+            character: CharacterIdentity::new(region, name, realm),
+            fields: CharacterDetailsFields::default(),
+            cache_mode: CacheMode::default(),
+        }
+    }
+    /// Builder for getting the information of many characters at once
+    pub fn character_details_batch<I>(&self, characters: I) -> CharacterDetailsBatchRequest<'_>
+    where
+        I: IntoIterator<Item = CharacterIdentity>,
+    {
+        CharacterDetailsBatchRequest {
+            client: self,
+            characters: characters.into_iter().collect(),
+            concurrency: 8,
+            fields: CharacterDetailsFields::default(),
+            cache_mode: CacheMode::default(),
+        }
+    }
+}
+
+/// Builder for a [`Client`](struct.Client.html)
+///
+/// The timeouts, user agent and default headers only apply to the http client built by
+/// [`build`](#method.build). They are ignored when a prebuilt client is supplied with
+/// [`http_client`](#method.http_client), or when a custom transport is supplied with
+/// [`transport`](#method.transport).
+#[derive(Debug)]
+pub struct ClientBuilder {
+    base_url: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    default_headers: reqwest::header::HeaderMap,
+    http_client: Option<reqwest::Client>,
+    transport: Option<Box<dyn Transport>>,
+    rate_limit: Option<u32>,
+    retry: Option<RetryPolicy>,
+    cache: Option<ResponseCache>,
+    disk_cache: Option<DiskCache>,
+    offline: bool,
+    access_key: Option<AccessKey>,
+    coalesce: bool,
+}
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl ClientBuilder {
+    pub fn new() -> ClientBuilder {
+        ClientBuilder {
+            base_url: BASE_URL.to_owned(),
+            timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            default_headers: reqwest::header::HeaderMap::new(),
+            http_client: None,
+            transport: None,
+            rate_limit: None,
+            retry: None,
+            cache: None,
+            disk_cache: None,
+            offline: false,
+            access_key: None,
+            coalesce: false,
+        }
+    }
+    /// Root of the API, defaults to `https://raider.io/api/v1`
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+    /// Timeout for a whole request, from connecting to reading the body
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// Timeout for the connect phase of a request
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// `User-Agent` header sent with every request
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+    /// Headers sent with every request, merged with any previously set
+    pub fn default_headers(mut self, headers: reqwest::header::HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+    /// Use an already configured http client
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+    /// Send the requests through a custom transport instead of reqwest
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+    /// Limit the client to `requests` per minute, queuing requests over the limit
+    ///
+    /// The limit is at least one request per minute, a limit of 0 is treated as 1.
+    pub fn rate_limit(mut self, requests: u32) -> Self {
+        self.rate_limit = Some(requests);
+        self
+    }
+    /// Retry requests failing with transient errors
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+    /// Keep successful responses in memory
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+    /// Keep successful responses on disk, to reuse them across runs
+    pub fn disk_cache(mut self, cache: DiskCache) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+    /// Never send requests, only serve responses from the caches, even stale ones
+    ///
+    /// Requests that are not cached fail with
+    /// [`Error::OfflineCacheMiss`](enum.Error.html#variant.OfflineCacheMiss).
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+    /// API access key sent with every request, to benefit from higher rate limits
+    pub fn access_key<S: Into<String>>(mut self, access_key: S) -> Self {
+        self.access_key = Some(AccessKey(access_key.into()));
+        self
+    }
+    /// Read the access key from the `RAIDER_IO_ACCESS_KEY` environment variable, if it is set
+    pub fn access_key_from_env(self) -> Self {
+        match std::env::var(ACCESS_KEY_VAR) {
+            Ok(access_key) if !access_key.is_empty() => self.access_key(access_key),
+            _ => self,
         }
     }
+    /// Share a single request between callers asking for the same endpoint and query at the
+    /// same time
+    ///
+    /// When coalescing is enabled, every failed request returns its error wrapped in
+    /// [`Error::Shared`](enum.Error.html#variant.Shared), even when no other caller was waiting
+    /// for it. Use [`Error::as_inner`](enum.Error.html#method.as_inner) to inspect the
+    /// underlying error.
+    pub fn coalesce(mut self, coalesce: bool) -> Self {
+        self.coalesce = coalesce;
+        self
+    }
+    pub fn build(self) -> Result<Client, Error> {
+        let transport = match (self.transport, self.http_client) {
+            (Some(transport), _) => transport,
+            (None, Some(http_client)) => Box::new(ReqwestTransport::new(http_client)),
+            (None, None) => {
+                let mut builder = reqwest::Client::builder().default_headers(self.default_headers);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                Box::new(ReqwestTransport::new(builder.build()?))
+            }
+        };
+        Ok(Client {
+            transport,
+            base_url: self.base_url.trim_end_matches('/').to_owned(),
+            rate_limiter: self.rate_limit.map(RateLimiter::per_minute),
+            retry: self.retry,
+            cache: self.cache,
+            disk_cache: self.disk_cache,
+            offline: self.offline,
+            access_key: self.access_key,
+            in_flight: if self.coalesce {
+                Some(Default::default())
+            } else {
+                None
+            },
+        })
+    }
 }
 
+#[derive(Default, Clone)]
 struct CharacterDetailsFields {
     gear: bool,
     guild: bool,
@@ -88,25 +874,6 @@ struct CharacterDetailsFields {
     raid_achievement_curve: Option<Vec<u8>>,
 }
 
-impl Default for CharacterDetailsFields {
-    fn default() -> Self {
-        CharacterDetailsFields {
-            gear: false,
-            guild: false,
-            raid_progression: false,
-            mythic_plus_ranks: false,
-            mythic_plus_recent_runs: false,
-            mythic_plus_weekly_higest_runs: false,
-            mythic_plus_best_runs: None,
-            mythic_plus_highest_runs: false,
-            mythic_plus_previous_week_ranking: false,
-            mythic_plus_previous_week_highest_runs: false,
-            mythic_plus_by_season: None,
-            raid_achievement_curve: None,
-            raid_achievement_meta: None,
-        }
-    }
-}
 impl CharacterDetailsFields {
     fn text(&self) -> String {
         CharacterDetailsFieldsIter {
@@ -115,6 +882,14 @@ impl CharacterDetailsFields {
         }
         .join(",")
     }
+    fn query(&self) -> Option<String> {
+        let fields = self.text();
+        if fields.is_empty() {
+            None
+        } else {
+            Some(fields)
+        }
+    }
 }
 
 macro_rules! s_str {
@@ -142,18 +917,7 @@ impl<'c> Iterator for CharacterDetailsFieldsIter<'c> {
                 3 if self.fields.mythic_plus_by_season.is_some() => {
                     let mut seasons = String::new();
                     for season in self.fields.mythic_plus_by_season.clone().unwrap() {
-                        match season {
-                            mythic_plus::Season::Previous => {
-                                seasons = format!("{}:previous", seasons);
-                            }
-                            mythic_plus::Season::Current => {
-                                seasons = format!("{}:current", seasons);
-                            }
-                            mythic_plus::Season::Specific { expansion, number } => {
-                                seasons =
-                                    format!("{}:season-{}-{}", seasons, expansion.text(), number);
-                            }
-                        }
+                        seasons = format!("{}:{}", seasons, season.text());
                     }
                     return Some(format!("mythic_plus_scores_by_season{}", seasons));
                 }
@@ -196,6 +960,43 @@ pub struct CharacterDetailsRequest<'c> {
     region: Region,
     realm: &'c str,
     fields: CharacterDetailsFields,
+    cache_mode: CacheMode,
+}
+
+/// Character looked up by a [`CharacterDetailsBatchRequest`](struct.CharacterDetailsBatchRequest.html)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CharacterIdentity {
+    pub region: Region,
+    pub name: String,
+    pub realm: String,
+}
+impl CharacterIdentity {
+    pub fn new<N: Into<String>, R: Into<String>>(region: Region, name: N, realm: R) -> Self {
+        CharacterIdentity {
+            region,
+            name: name.into(),
+            realm: realm.into(),
+        }
+    }
+}
+
+/// Same as [`CharacterDetailsRequest`](struct.CharacterDetailsRequest.html), without any
+/// borrow, so that it can be executed any number of times from any task
+#[derive(Clone)]
+pub struct OwnedCharacterDetailsRequest {
+    client: Arc<Client>,
+    character: CharacterIdentity,
+    fields: CharacterDetailsFields,
+    cache_mode: CacheMode,
+}
+
+/// Information about many characters, fetched concurrently with the same fields
+pub struct CharacterDetailsBatchRequest<'c> {
+    client: &'c Client,
+    characters: Vec<CharacterIdentity>,
+    concurrency: usize,
+    fields: CharacterDetailsFields,
+    cache_mode: CacheMode,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -242,131 +1043,440 @@ struct ApiError {
     message: String,
 }
 
+/// Methods selecting the fields of a character request, shared by every request builder
+/// holding `fields: CharacterDetailsFields` and `cache_mode: CacheMode`
+macro_rules! character_fields {
+    () => {
+        /// Clear all the fields from the request
+        pub fn clear(mut self) -> Self {
+            self.fields = Default::default();
+            self
+        }
+        /// retrieve basic information about guild the player is in
+        pub fn guild(mut self) -> Self {
+            self.fields.guild = true;
+            self
+        }
+        /// retrieve high level item information for player
+        pub fn gear(mut self) -> Self {
+            self.fields.gear = true;
+            self
+        }
+        /// retrieve scores by mythic plus season
+        pub fn mythic_plus_score_by_season(mut self, season: mythic_plus::Season) -> Self {
+            match &mut self.fields.mythic_plus_by_season {
+                None => {
+                    let mut set = HashSet::new();
+                    set.insert(season);
+                    self.fields.mythic_plus_by_season = Some(set);
+                }
+                Some(ref mut set) => {
+                    set.insert(season);
+                }
+            };
+            self
+        }
+
+        /// current season mythic plus rankings for player.
+        pub fn mythic_plus_ranks(mut self) -> Self {
+            self.fields.mythic_plus_ranks = true;
+            self
+        }
+
+        /// retrieve raid progression data for character
+        pub fn raid_progression(mut self) -> Self {
+            self.fields.raid_progression = true;
+            self
+        }
+
+        /// retrieve three most recent mythic plus runs for player (current season only).
+        pub fn mythic_plus_recent_runs(mut self) -> Self {
+            self.fields.mythic_plus_recent_runs = true;
+            self
+        }
+
+        /// retrieve all of a character's best runs for the season
+        pub fn mythic_plus_all_best_runs(mut self) -> Self {
+            self.fields.mythic_plus_best_runs = Some(mythic_plus::SeasonBestRuns::All);
+            self
+        }
+        /// retrieve three most high scoring mythic plus runs for player (current season only).
+        pub fn mythic_plus_three_best_runs(mut self) -> Self {
+            self.fields.mythic_plus_best_runs = Some(mythic_plus::SeasonBestRuns::Three);
+            self
+        }
+        /// retrieve the player's three highest Mythic+ runs by Mythic+ level (current season only)
+        pub fn mythic_plus_highest_runs(mut self) -> Self {
+            self.fields.mythic_plus_highest_runs = true;
+            self
+        }
+        /// retrieve the player's three highest Mythic+ runs by Mythic+ level for the current raid week (current season only)
+        pub fn mythic_plus_weekly_highest_level_runs(mut self) -> Self {
+            self.fields.mythic_plus_weekly_higest_runs = true;
+            self
+        }
+        /// retrieve the player's three highest Mythic+ runs by Mythic+ level for the previous raid week (current season only)
+        pub fn mythic_plus_previous_weekly_highest_level_runs(mut self) -> Self {
+            self.fields.mythic_plus_previous_week_highest_runs = true;
+            self
+        }
+        /// retrieve mythic plus rankings for player.
+        pub fn previous_mythic_plus_ranks(mut self) -> Self {
+            self.fields.mythic_plus_previous_week_ranking = true;
+            self
+        }
+
+        /// Choose how this request uses the response cache of the client
+        pub fn cache_mode(mut self, mode: CacheMode) -> Self {
+            self.cache_mode = mode;
+            self
+        }
+    };
+}
+
 impl<'c> CharacterDetailsRequest<'c> {
-    /// Clear all the fields from the request
-    pub fn clear(mut self) -> Self {
-        self.fields = Default::default();
-        self
-    }
-    /// retrieve basic information about guild the player is in
-    pub fn guild(mut self) -> Self {
-        self.fields.guild = true;
-        self
+    character_fields!();
+
+    /// Execute the query on the raider.io website
+    pub async fn get(&self) -> Result<CharacterDetails, Error> {
+        let query = CharacterQuery {
+            name: self.name,
+            region: self.region,
+            realm: self.realm,
+            fields: self.fields.query(),
+        };
+        self.client
+            .get("characters/profile", &query, self.cache_mode)
+            .await
     }
-    /// retrieve high level item information for player
-    pub fn gear(mut self) -> Self {
-        self.fields.gear = true;
-        self
+}
+
+impl OwnedCharacterDetailsRequest {
+    character_fields!();
+
+    /// Character targeted by the request
+    pub fn character(&self) -> &CharacterIdentity {
+        &self.character
     }
-    /// retrieve scores by mythic plus season
-    pub fn mythic_plus_score_by_season(mut self, season: mythic_plus::Season) -> Self {
-        match &mut self.fields.mythic_plus_by_season {
-            None => {
-                let mut set = HashSet::new();
-                set.insert(season);
-                self.fields.mythic_plus_by_season = Some(set);
-            }
-            Some(ref mut set) => {
-                set.insert(season);
-            }
+
+    /// Execute the query on the raider.io website
+    pub async fn get(&self) -> Result<CharacterDetails, Error> {
+        let query = CharacterQuery {
+            name: &self.character.name,
+            region: self.character.region,
+            realm: &self.character.realm,
+            fields: self.fields.query(),
         };
-        self
+        self.client
+            .get("characters/profile", &query, self.cache_mode)
+            .await
     }
+}
+
+impl<'c> CharacterDetailsBatchRequest<'c> {
+    character_fields!();
 
-    /// current season mythic plus rankings for player.
-    pub fn mythic_plus_ranks(mut self) -> Self {
-        self.fields.mythic_plus_ranks = true;
+    /// Maximum number of requests in flight at the same time, defaults to 8
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
         self
     }
 
-    /// retrieve raid progression data for character
-    pub fn raid_progression(mut self) -> Self {
-        self.fields.raid_progression = true;
-        self
+    /// Execute the queries, returning the result of each character in the order they were given
+    pub async fn get(&self) -> Vec<Result<CharacterDetails, Error>> {
+        let fields = self.fields.query();
+        futures::stream::iter(&self.characters)
+            .map(|character| {
+                let query = CharacterQuery {
+                    name: &character.name,
+                    region: character.region,
+                    realm: &character.realm,
+                    fields: fields.clone(),
+                };
+                async move {
+                    self.client
+                        .get("characters/profile", &query, self.cache_mode)
+                        .await
+                }
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await
     }
+}
 
-    /// retrieve three most recent mythic plus runs for player (current season only).
-    pub fn mythic_plus_recent_runs(mut self) -> Self {
-        self.fields.mythic_plus_recent_runs = true;
-        self
+/// Client answering the first page of a paginated endpoint with `page`, and the second one with
+/// `empty_page`
+#[cfg(test)]
+pub(crate) fn paged_client(path: &str, query: &str, page: &str, empty_page: &str) -> Client {
+    let transport = InMemoryTransport::new()
+        .respond(path, &format!("{}&page=0", query), StatusCode::OK, page)
+        .respond(
+            path,
+            &format!("{}&page=1", query),
+            StatusCode::OK,
+            empty_page,
+        );
+    Client::builder().transport(transport).build().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        assert_eq!(2 + 2, 4);
     }
 
-    /// retrieve all of a character's best runs for the season
-    pub fn mythic_plus_all_best_runs(mut self) -> Self {
-        self.fields.mythic_plus_best_runs = Some(mythic_plus::SeasonBestRuns::All);
-        self
+    #[test]
+    fn base_url_trailing_slash() {
+        let client = Client::builder()
+            .base_url("http://localhost:8080/api/v1/")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.endpoint("characters/profile"),
+            "http://localhost:8080/api/v1/characters/profile"
+        );
     }
-    /// retrieve three most high scoring mythic plus runs for player (current season only).
-    pub fn mythic_plus_three_best_runs(mut self) -> Self {
-        self.fields.mythic_plus_best_runs = Some(mythic_plus::SeasonBestRuns::Three);
-        self
+
+    fn parse(status: u16, headers: &HeaderMap, body: &str) -> Result<serde_json::Value, Error> {
+        parse_response(
+            StatusCode::from_u16(status).unwrap(),
+            headers,
+            body.to_owned(),
+        )
     }
-    /// retrieve the player's three highest Mythic+ runs by Mythic+ level (current season only)
-    pub fn mythic_plus_highest_runs(mut self) -> Self {
-        self.fields.mythic_plus_highest_runs = true;
-        self
+
+    #[test]
+    fn classify_errors() {
+        let headers = HeaderMap::new();
+        let not_found = r#"{"statusCode":400,"error":"Bad Request","message":"Could not find requested character"}"#;
+        assert!(matches!(
+            parse(400, &headers, not_found),
+            Err(Error::CharacterNotFound { .. })
+        ));
+        assert!(matches!(
+            parse(400, &headers, "<html>"),
+            Err(Error::UnexpectedBody { status: 400, .. })
+        ));
+        assert!(matches!(
+            parse(503, &headers, "down"),
+            Err(Error::Server { status: 503, .. })
+        ));
+        assert!(matches!(
+            parse(302, &headers, ""),
+            Err(Error::UnexpectedStatus { status: 302, .. })
+        ));
     }
-    /// retrieve the player's three highest Mythic+ runs by Mythic+ level for the current raid week (current season only)
-    pub fn mythic_plus_weekly_highest_level_runs(mut self) -> Self {
-        self.fields.mythic_plus_weekly_higest_runs = true;
-        self
+
+    const CHARACTER: &str = r#"{
+        "name": "Andybrew",
+        "race": "Pandaren",
+        "class": "Monk",
+        "active_spec_name": "Brewmaster",
+        "active_spec_role": "TANK",
+        "gender": "male",
+        "faction": "horde",
+        "region": "eu",
+        "realm": "Draenor",
+        "profile_url": "https://raider.io/characters/eu/draenor/Andybrew",
+        "achievement_points": 10000,
+        "honorable_kills": 0,
+        "thumbnail_url": "https://render.worldofwarcraft.com/andybrew.jpg"
+    }"#;
+
+    #[tokio::test]
+    async fn in_memory_transport() {
+        let transport = std::sync::Arc::new(InMemoryTransport::new().respond(
+            "characters/profile",
+            "region=eu&realm=Draenor&name=Andybrew",
+            StatusCode::OK,
+            CHARACTER,
+        ));
+        let client = Client::builder()
+            .transport(transport.clone())
+            .cache(ResponseCache::new(Duration::from_secs(60)))
+            .build()
+            .unwrap();
+
+        let details = client
+            .character_details(Region::Europe, "Andybrew", "Draenor")
+            .get()
+            .await
+            .unwrap();
+        assert_eq!(details.name, "Andybrew");
+        client
+            .character_details(Region::Europe, "Andybrew", "Draenor")
+            .get()
+            .await
+            .unwrap();
+        assert_eq!(transport.requests().len(), 1);
+
+        let missing = client
+            .character_details(Region::Europe, "Nobody", "Draenor")
+            .get()
+            .await;
+        assert!(matches!(missing, Err(Error::Api { status: 404, .. })));
     }
-    /// retrieve the player's three highest Mythic+ runs by Mythic+ level for the previous raid week (current season only)
-    pub fn mythic_plus_previous_weekly_highest_level_runs(mut self) -> Self {
-        self.fields.mythic_plus_previous_week_highest_runs = true;
-        self
+
+    #[tokio::test]
+    async fn access_key() {
+        let transport = std::sync::Arc::new(InMemoryTransport::new());
+        let client = Client::builder()
+            .transport(transport.clone())
+            .access_key("s3cr3t")
+            .build()
+            .unwrap();
+        assert!(!format!("{:?}", client).contains("s3cr3t"));
+
+        let error = client
+            .character_details(Region::Europe, "Andybrew", "Draenor")
+            .get()
+            .await
+            .unwrap_err();
+        assert!(transport.requests()[0].contains("access_key=s3cr3t"));
+        assert!(!format!("{:?}", error).contains("s3cr3t"));
     }
-    /// retrieve mythic plus rankings for player.
-    pub fn previous_mythic_plus_ranks(mut self) -> Self {
-        self.fields.mythic_plus_previous_week_ranking = true;
-        self
+
+    #[tokio::test]
+    async fn batch_keeps_order() {
+        let transport = InMemoryTransport::new().respond(
+            "characters/profile",
+            "region=eu&realm=Draenor&name=Andybrew&fields=guild",
+            StatusCode::OK,
+            CHARACTER,
+        );
+        let client = Client::builder().transport(transport).build().unwrap();
+        let results = client
+            .character_details_batch(vec![
+                CharacterIdentity::new(Region::Europe, "Nobody", "Draenor"),
+                CharacterIdentity::new(Region::Europe, "Andybrew", "Draenor"),
+            ])
+            .guild()
+            .concurrency(2)
+            .get()
+            .await;
+        assert!(results[0].is_err());
+        assert_eq!(results[1].as_ref().unwrap().name, "Andybrew");
     }
 
-    /// Execute the query on the raider.io website
-    pub async fn get(&self) -> Result<CharacterDetails, Error> {
-        let fields = self.fields.text();
-        let fields = if fields.is_empty() {
-            None
-        } else {
-            Some(fields)
-        };
+    /// Fails with a server error on the first request, then answers like the wrapped transport
+    #[derive(Debug)]
+    struct FlakyTransport(InMemoryTransport, std::sync::atomic::AtomicBool);
 
-        let query = CharacterQuery {
-            name: self.name,
-            region: self.region,
-            realm: self.realm,
-            fields,
-        };
-        let response = self
-            .client
-            .http_client
-            .get(&format!("{}/characters/profile", BASE_URL))
-            .query(&query)
-            .send()
-            .await?;
+    #[async_trait::async_trait]
+    impl Transport for FlakyTransport {
+        async fn get(&self, url: &str, query: &str) -> Result<transport::Response, Error> {
+            if self.1.swap(false, std::sync::atomic::Ordering::SeqCst) {
+                return Ok(transport::Response {
+                    status: StatusCode::SERVICE_UNAVAILABLE,
+                    headers: reqwest::header::HeaderMap::new(),
+                    body: String::new(),
+                });
+            }
+            self.0.get(url, query).await
+        }
+    }
 
-        if response.status().is_client_error() {
-            let response: ApiError = response.json().await?;
-            Err(Error::Api {
-                status: response.status_code,
-                error: response.error,
-                message: response.message,
-            })
-        } else if response.status().is_success() {
-            response
-                .json::<CharacterDetails>()
-                .await
-                .map_err(From::from)
-        } else {
-            panic!("Unhandled status code");
+    #[tokio::test]
+    async fn report_attempts_of_retried_requests() {
+        let client = Client::builder()
+            .transport(FlakyTransport(InMemoryTransport::new(), true.into()))
+            .retry(RetryPolicy::new(3).base_delay(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+        let error = client
+            .character_details(Region::Europe, "Nobody", "Draenor")
+            .get()
+            .await
+            .unwrap_err();
+        match error {
+            Error::RetriesExhausted { attempts, last } => {
+                assert_eq!(attempts, 2);
+                assert!(!last.is_transient());
+            }
+            error => panic!("unexpected error: {:?}", error),
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
+    #[derive(Debug)]
+    struct SlowTransport(std::sync::Arc<InMemoryTransport>);
+
+    #[async_trait::async_trait]
+    impl Transport for SlowTransport {
+        async fn get(&self, url: &str, query: &str) -> Result<transport::Response, Error> {
+            tokio::time::delay_for(Duration::from_millis(20)).await;
+            self.0.get(url, query).await
+        }
+    }
+
+    #[tokio::test]
+    async fn coalesce_identical_requests() {
+        let transport = std::sync::Arc::new(InMemoryTransport::new().respond(
+            "characters/profile",
+            "region=eu&realm=Draenor&name=Andybrew",
+            StatusCode::OK,
+            CHARACTER,
+        ));
+        let client = Client::builder()
+            .transport(SlowTransport(transport.clone()))
+            .coalesce(true)
+            .build()
+            .unwrap();
+        let request = client.character_details(Region::Europe, "Andybrew", "Draenor");
+        let (first, second) = futures::join!(request.get(), request.get());
+        assert_eq!(first.unwrap().name, second.unwrap().name);
+        assert_eq!(transport.requests().len(), 1);
+
+        let request = client.character_details(Region::Europe, "Nobody", "Draenor");
+        let (first, second) = futures::join!(request.get(), request.get());
+        assert!(matches!(first, Err(Error::Shared(_))));
+        assert!(matches!(second, Err(Error::Shared(_))));
+        assert_eq!(transport.requests().len(), 2);
+
+        let error = request.get().await.unwrap_err();
+        assert!(matches!(error, Error::Shared(_)));
+        assert!(matches!(error.as_inner(), Error::Api { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn spawn_owned_request() {
+        let transport = InMemoryTransport::new().respond(
+            "characters/profile",
+            "region=eu&realm=Draenor&name=Andybrew&fields=gear",
+            StatusCode::OK,
+            CHARACTER,
+        );
+        let client = Arc::new(Client::builder().transport(transport).build().unwrap());
+        let request = client
+            .owned_character_details(Region::Europe, "Andybrew", "Draenor")
+            .gear();
+        let spawned = request.clone();
+        let details = tokio::spawn(async move { spawned.get().await })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(details.name, "Andybrew");
+        assert!(request.get().await.is_ok());
+    }
+
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn rate_limited_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "12".parse().unwrap());
+        match parse(429, &headers, "") {
+            Err(Error::RateLimited { retry_after }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(12)))
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match parse(503, &headers, "down") {
+            Err(Error::Server { retry_after, .. }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(12)))
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
     }
 }
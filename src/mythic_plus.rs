@@ -1,4 +1,7 @@
+use crate::{player, CacheMode, Client, Error, Expansion, Region, RegionDates, Request};
+use futures::Stream;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize)]
 pub enum Season {
@@ -9,15 +12,33 @@ pub enum Season {
         number: u8,
     },
 }
+impl Season {
+    pub(crate) fn text(&self) -> String {
+        match self {
+            Season::Previous => "previous".to_owned(),
+            Season::Current => "current".to_owned(),
+            Season::Specific { expansion, number } => {
+                format!("season-{}-{}", expansion.text(), number)
+            }
+        }
+    }
+}
 impl std::str::FromStr for Season {
     type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "previous" => return Ok(Season::Previous),
+            "current" => return Ok(Season::Current),
+            _ => (),
+        }
         let mut iter = s.split("-");
         iter.next();
         let expansion = match iter.next() {
             None => Err("no expansion")?,
-            Some("bfa") => crate::Expansion::BattleForAzeroth,
-            Some(_) => Err("unknown expansion")?,
+            Some(expansion) => match crate::Expansion::from_text(expansion) {
+                Some(expansion) => expansion,
+                None => Err("unknown expansion")?,
+            },
         };
         let number = match iter.next().map(|s| s.parse()) {
             None => Err("no season number")?,
@@ -27,6 +48,12 @@ impl std::str::FromStr for Season {
         Ok(Season::Specific { expansion, number })
     }
 }
+fn ser_season<S>(season: &Season, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&season.text())
+}
 fn deser_season<'de, D>(deserializer: D) -> Result<Season, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -90,7 +117,7 @@ pub struct MythicPlusRanks {
 }
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 pub struct MythicPlusScores {
-    #[serde(deserialize_with = "deser_season")]
+    #[serde(serialize_with = "ser_season", deserialize_with = "deser_season")]
     pub season: Season,
     pub scores: Scores,
 }
@@ -132,6 +159,10 @@ pub enum Dungeon {
     SiegeOfBoralus,
     #[serde(rename = "Waycrest Manor")]
     WaycrestManor,
+    /// Dungeon of another expansion, see [`DungeonRegistry`](struct.DungeonRegistry.html) to
+    /// resolve it from the `map_challenge_mode_id` of the run
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
@@ -153,6 +184,9 @@ pub enum AffixName {
     Reaping,
     Beguiling,
     Awakened,
+    /// Affix added after this version of the crate, or named in a language other than english
+    #[serde(other)]
+    Unknown,
 }
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Affix {
@@ -171,4 +205,770 @@ pub struct KeystoneRun {
     pub map_challenge_mode_id: u64,
     pub score: f32,
     pub affixes: Vec<Affix>,
+    pub keystone_run_id: Option<u64>,
+    pub url: Option<String>,
+}
+impl KeystoneRun {
+    /// Id of the run, usable with
+    /// [`Client::mythic_plus_run_details`](../struct.Client.html#method.mythic_plus_run_details),
+    /// taken from the url of the run when the API does not give it directly
+    pub fn run_id(&self) -> Option<u64> {
+        self.keystone_run_id.or_else(|| {
+            let slug = self.url.as_ref()?.rsplit('/').next()?;
+            slug.split('-').next()?.parse().ok()
+        })
+    }
+}
+
+/// Affixes active during the current week
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct WeeklyAffixes {
+    pub region: Region,
+    /// Names of the affixes, separated by commas
+    pub title: String,
+    pub leaderboard_url: String,
+    #[serde(rename = "affix_details")]
+    pub affixes: Vec<Affix>,
+}
+
+/// Affix as given in the `weekly_modifiers` of a run
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct WeeklyModifier {
+    pub id: u64,
+    pub icon: String,
+    pub name: AffixName,
+    pub description: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RunMember {
+    pub character: player::RosterCharacter,
+    pub role: player::Role,
+}
+
+/// Dungeon as described in a leaderboard run
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RunDungeon {
+    /// Zone id of the dungeon, the `id` of a [`StaticDungeon`](struct.StaticDungeon.html)
+    pub id: u64,
+    pub name: String,
+    pub short_name: String,
+    pub slug: String,
+}
+
+/// Run of a leaderboard
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LeaderboardRun {
+    #[serde(serialize_with = "ser_season", deserialize_with = "deser_season")]
+    pub season: Season,
+    pub dungeon: RunDungeon,
+    pub keystone_run_id: u64,
+    pub mythic_level: u8,
+    pub completed_at: chrono::DateTime<chrono::Utc>,
+    pub clear_time_ms: u64,
+    pub keystone_time_ms: u64,
+    pub time_remaining_ms: i64,
+    /// Number of chests obtained, which is the number of keystone upgrades
+    pub num_chests: u8,
+    pub weekly_modifiers: Vec<WeeklyModifier>,
+    pub roster: Vec<RunMember>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RankedRun {
+    pub rank: u64,
+    pub score: f32,
+    pub run: LeaderboardRun,
+}
+
+#[derive(Deserialize)]
+struct RunsPage {
+    rankings: Vec<RankedRun>,
+}
+impl crate::Page for RunsPage {
+    type Item = RankedRun;
+    fn into_items(self) -> Vec<RankedRun> {
+        self.rankings
+    }
+}
+
+/// Builder for the mythic plus runs leaderboard
+pub struct RunsRequest<'c> {
+    client: &'c Client,
+    season: Season,
+    region: Option<Region>,
+    dungeon: String,
+    affixes: String,
+    max_pages: Option<u32>,
+    cache_mode: CacheMode,
+}
+
+impl<'c> RunsRequest<'c> {
+    /// Season of the runs, defaults to the current one
+    pub fn season(mut self, season: Season) -> Self {
+        self.season = season;
+        self
+    }
+    /// Only rank the runs of a dungeon identified by its slug (for example `ataldazar`)
+    pub fn dungeon<S: Into<String>>(mut self, dungeon: S) -> Self {
+        self.dungeon = dungeon.into();
+        self
+    }
+    /// Only rank the runs done with some affixes, either `current`, `all` or the slugs of the
+    /// affixes joined by dashes (for example `fortified-bolstering-grievous-awakened`)
+    pub fn affixes<S: Into<String>>(mut self, affixes: S) -> Self {
+        self.affixes = affixes.into();
+        self
+    }
+    paged_request_setters!();
+
+    /// Runs of the leaderboard, best first, fetching pages as the stream is polled
+    pub fn stream(&self) -> impl Stream<Item = Result<RankedRun, Error>> + 'c {
+        let query = vec![
+            ("season", self.season.text()),
+            ("region", crate::region_or_world(self.region).to_owned()),
+            ("dungeon", self.dungeon.clone()),
+            ("affixes", self.affixes.clone()),
+        ];
+        self.client
+            .pages::<RunsPage>("mythic-plus/runs", query, self.cache_mode, self.max_pages)
+    }
+}
+
+/// Item levels of a character during a run
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+pub struct RunItems {
+    pub item_level_equipped: f32,
+    pub item_level_total: f32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Covenant {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RunDetailsMember {
+    pub character: player::RosterCharacter,
+    pub role: player::Role,
+    pub items: Option<RunItems>,
+    pub covenant: Option<Covenant>,
+}
+
+/// Timestamps of a run, when it was logged by a member of the group
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LoggedDetails {
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RunDetails {
+    #[serde(serialize_with = "ser_season", deserialize_with = "deser_season")]
+    pub season: Season,
+    pub dungeon: RunDungeon,
+    pub keystone_run_id: u64,
+    pub keystone_team_id: Option<u64>,
+    pub mythic_level: u8,
+    pub completed_at: chrono::DateTime<chrono::Utc>,
+    pub clear_time_ms: u64,
+    pub keystone_time_ms: u64,
+    pub time_remaining_ms: i64,
+    pub num_chests: u8,
+    pub faction: Option<player::Faction>,
+    pub weekly_modifiers: Vec<WeeklyModifier>,
+    pub roster: Vec<RunDetailsMember>,
+    #[serde(alias = "num_deaths")]
+    pub deaths: Option<u32>,
+    pub logged_run_id: Option<u64>,
+    pub logged_details: Option<LoggedDetails>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StaticDungeon {
+    pub id: u64,
+    pub challenge_mode_id: u64,
+    pub slug: String,
+    pub name: String,
+    pub short_name: String,
+    /// Time limit of a keystone, to complete it in time
+    pub keystone_timer_seconds: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StaticSeason {
+    pub slug: String,
+    pub name: String,
+    pub short_name: String,
+    pub starts: RegionDates,
+    pub ends: RegionDates,
+    pub dungeons: Vec<StaticDungeon>,
+}
+
+/// Seasons and dungeons of an expansion
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StaticData {
+    pub seasons: Vec<StaticSeason>,
+    pub dungeons: Vec<StaticDungeon>,
+}
+impl StaticData {
+    pub fn dungeon_registry(&self) -> DungeonRegistry {
+        DungeonRegistry::new(self)
+    }
+}
+
+/// Lookup of dungeon metadata, built from [`StaticData`](struct.StaticData.html)
+#[derive(Clone, Debug, Default)]
+pub struct DungeonRegistry {
+    dungeons: HashMap<u64, StaticDungeon>,
+}
+impl DungeonRegistry {
+    /// Registry of every dungeon of the static data, including the ones of its seasons
+    pub fn new(data: &StaticData) -> DungeonRegistry {
+        let mut registry = DungeonRegistry::default();
+        registry.extend(data);
+        registry
+    }
+    /// Add the dungeons of another expansion
+    pub fn extend(&mut self, data: &StaticData) {
+        let seasons = data.seasons.iter().flat_map(|season| &season.dungeons);
+        for dungeon in data.dungeons.iter().chain(seasons) {
+            self.dungeons
+                .insert(dungeon.challenge_mode_id, dungeon.clone());
+        }
+    }
+    /// Dungeon with the `map_challenge_mode_id` of a run
+    pub fn get(&self, map_challenge_mode_id: u64) -> Option<&StaticDungeon> {
+        self.dungeons.get(&map_challenge_mode_id)
+    }
+    pub fn by_slug(&self, slug: &str) -> Option<&StaticDungeon> {
+        self.dungeons.values().find(|dungeon| dungeon.slug == slug)
+    }
+    pub fn for_run(&self, run: &KeystoneRun) -> Option<&StaticDungeon> {
+        self.get(run.map_challenge_mode_id)
+    }
+    /// Dungeon of a leaderboard run or of run details, matched on its zone id
+    pub fn for_run_dungeon(&self, dungeon: &RunDungeon) -> Option<&StaticDungeon> {
+        self.dungeons.values().find(|known| known.id == dungeon.id)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ScoreTier {
+    pub score: f32,
+    #[serde(rename = "rgbHex")]
+    pub rgb_hex: String,
+}
+
+/// Colors of the scores of a season
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct ScoreTiers {
+    pub tiers: Vec<ScoreTier>,
+}
+impl ScoreTiers {
+    /// Hex color (`#rrggbb`) of a score, as in [`Scores`](struct.Scores.html)
+    ///
+    /// The color is interpolated between the two tiers surrounding the score. Scores outside of
+    /// the table take the color of the lowest or the highest tier, and there is no color only
+    /// when the table is empty.
+    pub fn color_for(&self, score: f32) -> Option<String> {
+        let mut tiers: Vec<_> = self.tiers.iter().collect();
+        tiers.sort_by(|a, b| a.score.total_cmp(&b.score));
+        let upper = match tiers.iter().position(|tier| tier.score > score) {
+            Some(0) => return tiers.first().map(|tier| tier.rgb_hex.clone()),
+            Some(upper) => upper,
+            None => return tiers.last().map(|tier| tier.rgb_hex.clone()),
+        };
+        let (low, high) = (tiers[upper - 1], tiers[upper]);
+        let (from, to) = match (parse_rgb(&low.rgb_hex), parse_rgb(&high.rgb_hex)) {
+            (Some(from), Some(to)) => (from, to),
+            _ => return Some(low.rgb_hex.clone()),
+        };
+        let ratio = (score - low.score) / (high.score - low.score);
+        let channel = |i: usize| {
+            let (from, to) = (f32::from(from[i]), f32::from(to[i]));
+            (from + (to - from) * ratio).round() as u8
+        };
+        Some(format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(0),
+            channel(1),
+            channel(2)
+        ))
+    }
+}
+
+fn parse_rgb(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 {
+        return None;
+    }
+    let [_, r, g, b] = u32::from_str_radix(hex, 16).ok()?.to_be_bytes();
+    Some([r, g, b])
+}
+
+/// Lowest score reaching a percentile of the players
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Cutoff {
+    #[serde(rename = "quantileMinValue")]
+    pub score: f32,
+    pub quantile_population_count: Option<u64>,
+    pub quantile_population_fraction: Option<f32>,
+    pub total_population_count: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+pub struct FactionCutoffs {
+    pub all: Cutoff,
+    pub horde: Cutoff,
+    pub alliance: Cutoff,
+}
+impl FactionCutoffs {
+    pub fn get(&self, faction: player::Faction) -> &Cutoff {
+        match faction {
+            player::Faction::Horde => &self.horde,
+            player::Faction::Alliance => &self.alliance,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Cutoffs {
+    updated_at: chrono::DateTime<chrono::Utc>,
+    p999: FactionCutoffs,
+    p990: FactionCutoffs,
+    p900: FactionCutoffs,
+    p750: FactionCutoffs,
+    p600: FactionCutoffs,
+}
+
+#[derive(Deserialize)]
+struct CutoffsResponse {
+    cutoffs: Cutoffs,
+}
+
+/// Score cutoffs of a season in a region
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+pub struct SeasonCutoffs {
+    #[serde(serialize_with = "ser_season", deserialize_with = "deser_season")]
+    pub season: Season,
+    pub region: Region,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Top 0.1% of the players, the cutoff of the season title
+    pub p999: FactionCutoffs,
+    /// Top 1% of the players
+    pub p990: FactionCutoffs,
+    /// Top 10% of the players
+    pub p900: FactionCutoffs,
+    /// Top 25% of the players
+    pub p750: FactionCutoffs,
+    /// Top 40% of the players
+    pub p600: FactionCutoffs,
+}
+
+pub struct SeasonCutoffsRequest<'c> {
+    client: &'c Client,
+    season: Season,
+    region: Region,
+    cache_mode: CacheMode,
+}
+impl<'c> SeasonCutoffsRequest<'c> {
+    /// Choose how this request uses the response cache of the client
+    pub fn cache_mode(mut self, mode: CacheMode) -> Self {
+        self.cache_mode = mode;
+        self
+    }
+
+    /// Execute the query on the raider.io website
+    pub async fn get(&self) -> Result<SeasonCutoffs, Error> {
+        let query = [
+            ("season", self.season.text()),
+            ("region", self.region.text().to_owned()),
+        ];
+        let response: CutoffsResponse = self
+            .client
+            .get("mythic-plus/season-cutoffs", &query, self.cache_mode)
+            .await?;
+        let cutoffs = response.cutoffs;
+        Ok(SeasonCutoffs {
+            season: self.season,
+            region: self.region,
+            updated_at: cutoffs.updated_at,
+            p999: cutoffs.p999,
+            p990: cutoffs.p990,
+            p900: cutoffs.p900,
+            p750: cutoffs.p750,
+            p600: cutoffs.p600,
+        })
+    }
+}
+
+impl Client {
+    /// Score cutoffs of the percentiles of players in a season
+    pub fn mythic_plus_season_cutoffs(
+        &self,
+        season: Season,
+        region: Region,
+    ) -> SeasonCutoffsRequest<'_> {
+        SeasonCutoffsRequest {
+            client: self,
+            season,
+            region,
+            cache_mode: CacheMode::default(),
+        }
+    }
+    /// Colors of the scores of a season
+    pub fn mythic_plus_score_tiers(&self, season: Season) -> Request<'_, ScoreTiers> {
+        Request::new(
+            self,
+            "mythic-plus/score-tiers",
+            vec![("season", season.text())],
+        )
+    }
+    /// Seasons and dungeons of an expansion
+    pub fn mythic_plus_static_data(&self, expansion: Expansion) -> Request<'_, StaticData> {
+        Request::new(
+            self,
+            "mythic-plus/static-data",
+            vec![("expansion_id", expansion.id().to_string())],
+        )
+    }
+    /// Full details of a run, identified by its `keystone_run_id`
+    pub fn mythic_plus_run_details(&self, season: Season, id: u64) -> Request<'_, RunDetails> {
+        Request::new(
+            self,
+            "mythic-plus/run-details",
+            vec![("season", season.text()), ("id", id.to_string())],
+        )
+    }
+    /// Builder for the leaderboard of mythic plus runs
+    pub fn mythic_plus_runs(&self) -> RunsRequest<'_> {
+        RunsRequest {
+            client: self,
+            season: Season::Current,
+            region: None,
+            dungeon: "all".to_owned(),
+            affixes: "all".to_owned(),
+            max_pages: None,
+            cache_mode: CacheMode::default(),
+        }
+    }
+    /// Affixes of the current week in a region, described in `locale` (for example `en`)
+    pub fn mythic_plus_affixes(&self, region: Region, locale: &str) -> Request<'_, WeeklyAffixes> {
+        Request::new(
+            self,
+            "mythic-plus/affixes",
+            vec![
+                ("region", region.text().to_owned()),
+                ("locale", locale.to_owned()),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weekly_affixes() {
+        let affixes: WeeklyAffixes = serde_json::from_str(
+            r#"{
+                "region": "eu",
+                "title": "Fortified, Bolstering, Grievous, Awakened",
+                "leaderboard_url": "https://raider.io/mythic-plus-rankings/season-bfa-4/all/eu/leaderboards-strict",
+                "affix_details": [
+                    {"id": 10, "name": "Fortified", "description": "", "wowhead_url": "https://wowhead.com/affix=10"},
+                    {"id": 121, "name": "Prideful", "description": "", "wowhead_url": "https://wowhead.com/affix=121"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(matches!(affixes.affixes[0].name, AffixName::Fortified));
+        assert!(matches!(affixes.affixes[1].name, AffixName::Unknown));
+    }
+
+    const RUNS: &str = r#"{"rankings": [{
+        "rank": 1,
+        "score": 250.4,
+        "run": {
+            "season": "season-bfa-4",
+            "dungeon": {"id": 9028, "name": "Atal'dazar", "short_name": "AD", "slug": "ataldazar"},
+            "keystone_run_id": 4183042,
+            "mythic_level": 30,
+            "completed_at": "2020-08-11T19:21:30.000Z",
+            "clear_time_ms": 1613488,
+            "keystone_time_ms": 1800999,
+            "time_remaining_ms": 187511,
+            "num_chests": 1,
+            "weekly_modifiers": [
+                {"id": 10, "icon": "ability_toughness", "name": "Fortified", "description": ""}
+            ],
+            "roster": [{
+                "character": {
+                    "name": "Andybrew",
+                    "class": {"id": 10, "name": "Monk", "slug": "monk"},
+                    "spec": {"id": 268, "name": "Brewmaster", "slug": "brewmaster"},
+                    "faction": "horde",
+                    "realm": {"id": 1, "name": "Draenor", "slug": "draenor"}
+                },
+                "role": "tank"
+            }]
+        }
+    }, {
+        "rank": 2,
+        "score": 480.2,
+        "run": {
+            "season": "season-tww-1",
+            "dungeon": {"id": 14971, "name": "The Dawnbreaker", "short_name": "DAWN", "slug": "the-dawnbreaker"},
+            "keystone_run_id": 9312775,
+            "mythic_level": 20,
+            "completed_at": "2024-11-02T10:04:12.000Z",
+            "clear_time_ms": 1740211,
+            "keystone_time_ms": 1860999,
+            "time_remaining_ms": 120788,
+            "num_chests": 1,
+            "weekly_modifiers": [
+                {"id": 148, "icon": "ability_mage_arcanebarrage", "name": "Xal'atath's Bargain: Ascendant", "description": ""}
+            ],
+            "roster": [{
+                "character": {
+                    "name": "Ellesmere",
+                    "class": {"id": 13, "name": "Evoker", "slug": "evoker"},
+                    "spec": {"id": 1473, "name": "Augmentation", "slug": "augmentation"},
+                    "faction": "alliance",
+                    "realm": {"id": 1, "name": "Draenor", "slug": "draenor"}
+                },
+                "role": "dps"
+            }, {
+                "character": {
+                    "name": "Newclass",
+                    "class": {"id": 14, "name": "Tinker", "slug": "tinker"},
+                    "spec": {"id": 1500, "name": "Gadgeteer", "slug": "gadgeteer"},
+                    "faction": "alliance",
+                    "realm": {"id": 1, "name": "Draenor", "slug": "draenor"}
+                },
+                "role": "dps"
+            }]
+        }
+    }]}"#;
+
+    #[tokio::test]
+    async fn season_cutoffs() {
+        let cutoff = r#"{
+            "all": {"quantileMinValue": 2876.5, "quantilePopulationCount": 120},
+            "horde": {"quantileMinValue": 2880.1},
+            "alliance": {"quantileMinValue": 2870.3}
+        }"#;
+        let body = format!(
+            r#"{{"cutoffs": {{
+                "updatedAt": "2021-03-01T12:00:00Z",
+                "p999": {0}, "p990": {0}, "p900": {0}, "p750": {0}, "p600": {0}
+            }}}}"#,
+            cutoff
+        );
+        let transport = crate::InMemoryTransport::new().respond(
+            "mythic-plus/season-cutoffs",
+            "season=season-sl-1&region=eu",
+            reqwest::StatusCode::OK,
+            body,
+        );
+        let client = Client::builder().transport(transport).build().unwrap();
+        let season = Season::Specific {
+            expansion: Expansion::Shadowlands,
+            number: 1,
+        };
+        let cutoffs = client
+            .mythic_plus_season_cutoffs(season, Region::Europe)
+            .get()
+            .await
+            .unwrap();
+        assert_eq!(cutoffs.season, season);
+        assert_eq!(cutoffs.p999.get(player::Faction::Horde).score, 2880.1);
+
+        let stored = serde_json::to_string(&cutoffs).unwrap();
+        let cutoffs: SeasonCutoffs = serde_json::from_str(&stored).unwrap();
+        assert_eq!(cutoffs.season, season);
+        assert_eq!(cutoffs.region, Region::Europe);
+    }
+
+    #[test]
+    fn score_colors() {
+        let tiers: ScoreTiers = serde_json::from_str(
+            r##"[
+                {"score": 3000, "rgbHex": "#ff8000", "rgbDecimal": 16744448},
+                {"score": 2500, "rgbHex": "#a335ee", "rgbDecimal": 10696174},
+                {"score": 200, "rgbHex": "#ffffff", "rgbDecimal": 16777215}
+            ]"##,
+        )
+        .unwrap();
+        assert_eq!(tiers.color_for(3200.).as_deref(), Some("#ff8000"));
+        assert_eq!(tiers.color_for(2500.).as_deref(), Some("#a335ee"));
+        assert_eq!(tiers.color_for(2750.).as_deref(), Some("#d15b77"));
+        assert_eq!(tiers.color_for(100.).as_deref(), Some("#ffffff"));
+        assert_eq!(ScoreTiers { tiers: vec![] }.color_for(100.), None);
+    }
+
+    #[test]
+    fn dungeon_registry() {
+        let data: StaticData = serde_json::from_str(
+            r#"{
+                "seasons": [{
+                    "slug": "season-sl-1",
+                    "name": "SL Season 1",
+                    "short_name": "SL1",
+                    "starts": {"us": "2020-12-08T15:00:00Z", "eu": "2020-12-09T07:00:00Z", "tw": null, "kr": null},
+                    "ends": {"us": null, "eu": null, "tw": null, "kr": null},
+                    "dungeons": [{
+                        "id": 13309,
+                        "challenge_mode_id": 377,
+                        "slug": "de-other-side",
+                        "name": "De Other Side",
+                        "short_name": "DOS",
+                        "keystone_timer_seconds": 2580
+                    }]
+                }],
+                "dungeons": []
+            }"#,
+        )
+        .unwrap();
+        assert!(data.seasons[0].starts.get(Region::Europe).is_some());
+        let registry = data.dungeon_registry();
+        assert_eq!(registry.get(377).unwrap().short_name, "DOS");
+        assert_eq!(registry.by_slug("de-other-side").unwrap().id, 13309);
+        assert!(registry.get(244).is_none());
+        let run_dungeon: RunDungeon = serde_json::from_str(
+            r#"{"id": 13309, "name": "De Other Side", "short_name": "DOS", "slug": "de-other-side"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            registry
+                .for_run_dungeon(&run_dungeon)
+                .unwrap()
+                .challenge_mode_id,
+            377
+        );
+    }
+
+    #[test]
+    fn run_id_from_url() {
+        let run: KeystoneRun = serde_json::from_str(
+            r#"{
+                "dungeon": "Atal'dazar",
+                "mythic_level": 15,
+                "completed_at": "2020-08-11T19:21:30.000Z",
+                "clear_time_ms": 1613488,
+                "num_keystone_upgrades": 1,
+                "map_challenge_mode_id": 244,
+                "score": 150.1,
+                "affixes": [],
+                "url": "https://raider.io/mythic-plus-runs/season-bfa-4/6473213-15-atal-dazar"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(run.run_id(), Some(6473213));
+    }
+
+    #[test]
+    fn run_details() {
+        let details: RunDetails = serde_json::from_str(
+            r#"{
+                "season": "season-sl-2",
+                "dungeon": {"id": 12916, "name": "The Necrotic Wake", "short_name": "NW", "slug": "the-necrotic-wake"},
+                "keystone_run_id": 7210348,
+                "keystone_team_id": 1802954,
+                "mythic_level": 24,
+                "completed_at": "2021-09-14T18:41:02.000Z",
+                "clear_time_ms": 1950233,
+                "keystone_time_ms": 2160999,
+                "time_remaining_ms": 210766,
+                "num_chests": 1,
+                "faction": "horde",
+                "weekly_modifiers": [
+                    {"id": 9, "icon": "achievement_boss_archaedas", "name": "Tyrannical", "description": ""},
+                    {"id": 128, "icon": "spell_animamaw_orb", "name": "Tormented", "description": ""}
+                ],
+                "roster": [{
+                    "character": {
+                        "name": "Andybrew",
+                        "class": {"id": 10, "name": "Monk", "slug": "monk"},
+                        "spec": {"id": 268, "name": "Brewmaster", "slug": "brewmaster"},
+                        "faction": "horde",
+                        "realm": {"id": 1, "name": "Draenor", "slug": "draenor"}
+                    },
+                    "role": "tank",
+                    "items": {"item_level_equipped": 251.4, "item_level_total": 252.1},
+                    "covenant": {"id": 3, "name": "Night Fae"}
+                }],
+                "num_deaths": 2,
+                "logged_run_id": 51283,
+                "logged_details": {"started_at": "2021-09-14T18:08:31.000Z", "completed_at": null}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(details.dungeon.slug, "the-necrotic-wake");
+        let stored = serde_json::to_string(&details).unwrap();
+        let stored: RunDetails = serde_json::from_str(&stored).unwrap();
+        assert_eq!(stored.season, details.season);
+        assert!(matches!(
+            details.weekly_modifiers[0].name,
+            AffixName::Tyrannical
+        ));
+        assert!(matches!(
+            details.weekly_modifiers[1].name,
+            AffixName::Unknown
+        ));
+        let member = &details.roster[0];
+        assert!(matches!(member.role, player::Role::Tank));
+        assert_eq!(member.items.unwrap().item_level_equipped, 251.4);
+        assert_eq!(member.covenant.as_ref().unwrap().name, "Night Fae");
+        assert_eq!(details.deaths, Some(2));
+        let logged = details.logged_details.unwrap();
+        assert!(logged.started_at.is_some());
+        assert!(logged.completed_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn runs_pages() {
+        use futures::StreamExt;
+        let query = "season=current&region=world&dungeon=all&affixes=all";
+        let client = crate::paged_client("mythic-plus/runs", query, RUNS, r#"{"rankings": []}"#);
+        let runs: Vec<_> = client.mythic_plus_runs().stream().collect().await;
+        assert_eq!(runs.len(), 2);
+        let run = runs[0].as_ref().unwrap();
+        assert_eq!(run.run.keystone_run_id, 4183042);
+        let stored = serde_json::to_string(run).unwrap();
+        let stored: RankedRun = serde_json::from_str(&stored).unwrap();
+        assert_eq!(stored.run.season, run.run.season);
+        assert_eq!(run.run.dungeon.slug, "ataldazar");
+        let dawnbreaker = &runs[1].as_ref().unwrap().run;
+        assert_eq!(dawnbreaker.dungeon.id, 14971);
+        assert_eq!(dawnbreaker.dungeon.short_name, "DAWN");
+        assert!(matches!(
+            dawnbreaker.weekly_modifiers[0].name,
+            AffixName::Unknown
+        ));
+        let evoker = &dawnbreaker.roster[0].character;
+        assert!(matches!(evoker.class, player::Class::Evoker));
+        assert!(matches!(evoker.spec, player::Spec::Augmentation));
+        let unknown = &dawnbreaker.roster[1].character;
+        assert!(matches!(unknown.class, player::Class::Unknown));
+        assert!(matches!(unknown.spec, player::Spec::Unknown));
+        assert_eq!(
+            run.run.roster[0].character.realm.as_deref(),
+            Some("Draenor")
+        );
+
+        let runs: Vec<_> = client
+            .mythic_plus_runs()
+            .max_pages(0)
+            .stream()
+            .collect()
+            .await;
+        assert!(runs.is_empty());
+    }
 }
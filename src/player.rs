@@ -1,4 +1,40 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Deserialize a value given either directly or as the `name` of an object, like the classes
+/// and specs of the newer endpoints (`{"id": 10, "name": "Monk", "slug": "monk"}`)
+pub(crate) fn deser_named<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Named<T> {
+        Plain(T),
+        Object { name: T },
+    }
+    match Named::deserialize(deserializer)? {
+        Named::Plain(value) | Named::Object { name: value } => Ok(value),
+    }
+}
+
+/// Deserialize a value given either directly or as the `slug` of an object, like the regions of
+/// the ranking endpoints (`{"name": "Europe", "slug": "eu", "short_name": "EU"}`)
+pub(crate) fn deser_slug<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Slugged<T> {
+        Plain(T),
+        Object { slug: T },
+    }
+    match Slugged::deserialize(deserializer)? {
+        Slugged::Plain(value) | Slugged::Object { slug: value } => Ok(value),
+    }
+}
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Guild {
@@ -36,6 +72,8 @@ pub enum Race {
     Mechagnome,
     Nightborne,
     Vulpera,
+    Dracthyr,
+    Earthen,
     #[serde(rename = "Night Elf")]
     NightElf,
     #[serde(rename = "Blood Elf")]
@@ -54,6 +92,9 @@ pub enum Race {
     MagharOrc,
     #[serde(rename = "Zandalari Troll")]
     ZandalariTroll,
+    /// Race added after this version of the crate
+    #[serde(other)]
+    Unknown,
 }
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 pub enum Class {
@@ -67,10 +108,14 @@ pub enum Class {
     Warlock,
     Monk,
     Druid,
+    Evoker,
     #[serde(rename = "Demon Hunter")]
     DemonHunter,
     #[serde(rename = "Death Knight")]
     DeathKnight,
+    /// Class added after this version of the crate
+    #[serde(other)]
+    Unknown,
 }
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 pub enum Spec {
@@ -107,12 +152,32 @@ pub enum Spec {
     Vengeance,
     Blood,
     Unholy,
+    Devastation,
+    Preservation,
+    Augmentation,
+    /// Specialization added after this version of the crate
+    #[serde(other)]
+    Unknown,
 }
 #[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 pub enum Role {
+    #[serde(alias = "dps")]
     DPS,
-    #[serde(rename = "TANK")]
+    #[serde(rename = "TANK", alias = "tank")]
     Tank,
-    #[serde(rename = "HEALING")]
+    #[serde(rename = "HEALING", alias = "healer")]
     Healing,
 }
+
+/// Summary of a character, as given in the rosters of kills and runs
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RosterCharacter {
+    pub name: String,
+    #[serde(deserialize_with = "deser_named")]
+    pub class: Class,
+    #[serde(deserialize_with = "deser_named")]
+    pub spec: Spec,
+    pub faction: Option<Faction>,
+    #[serde(default, deserialize_with = "deser_named")]
+    pub realm: Option<String>,
+}
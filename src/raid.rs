@@ -1,4 +1,7 @@
+use crate::{player, CacheMode, Client, Error, Expansion, Region, RegionDates, Request};
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Progression {
@@ -8,16 +11,760 @@ pub struct Progression {
     pub heroic_bosses_killed: u8,
     pub mythic_bosses_killed: u8,
 }
+/// Progression in every raid of the expansion, by raid slug
 #[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(transparent)]
 pub struct RaidProgression {
-    #[serde(rename = "battle-of-dazaralor")]
-    pub battle_of_dazaralor: Progression,
-    #[serde(rename = "crucible-of-storms")]
-    pub crucible_of_storms: Progression,
-    #[serde(rename = "nyalotha-the-waking-city")]
-    pub nyalotha_the_waking_city: Progression,
-    #[serde(rename = "the-eternal-palace")]
-    pub the_eternal_palace: Progression,
-    #[serde(rename = "uldir")]
-    pub uldir: Progression,
+    pub raids: HashMap<String, Progression>,
+}
+impl RaidProgression {
+    /// Progression in a raid, identified by its slug (for example `nyalotha-the-waking-city`)
+    pub fn get(&self, raid: &str) -> Option<&Progression> {
+        self.raids.get(raid)
+    }
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    #[serde(rename = "normal")]
+    Normal,
+    #[serde(rename = "heroic")]
+    Heroic,
+    #[serde(rename = "mythic")]
+    Mythic,
+}
+impl Difficulty {
+    pub(crate) fn text(&self) -> &'static str {
+        match self {
+            Difficulty::Normal => "normal",
+            Difficulty::Heroic => "heroic",
+            Difficulty::Mythic => "mythic",
+        }
+    }
+}
+
+/// Guild as given in the rankings
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RankedGuild {
+    pub id: Option<u64>,
+    pub name: String,
+    pub faction: player::Faction,
+    #[serde(deserialize_with = "player::deser_named")]
+    pub realm: String,
+    #[serde(deserialize_with = "player::deser_slug")]
+    pub region: Region,
+    pub path: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DefeatedEncounter {
+    /// Slug of the boss
+    pub slug: String,
+    pub first_defeated: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_defeated: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RaidRanking {
+    pub rank: u64,
+    pub region_rank: Option<u64>,
+    pub guild: RankedGuild,
+    pub encounters_defeated: Vec<DefeatedEncounter>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RaidRankingsPage {
+    raid_rankings: Vec<RaidRanking>,
+}
+impl crate::Page for RaidRankingsPage {
+    type Item = RaidRanking;
+    fn into_items(self) -> Vec<RaidRanking> {
+        self.raid_rankings
+    }
+}
+
+/// Query shared by the rankings of a raid and of its bosses
+fn rankings_query(
+    raid: &str,
+    difficulty: Difficulty,
+    region: Option<Region>,
+    realm: &Option<String>,
+) -> Vec<(&'static str, String)> {
+    let mut query = vec![
+        ("raid", raid.to_owned()),
+        ("difficulty", difficulty.text().to_owned()),
+        ("region", crate::region_or_world(region).to_owned()),
+    ];
+    if let Some(realm) = realm {
+        query.push(("realm", realm.clone()));
+    }
+    query
+}
+
+/// Builder for the rankings of guilds in a raid
+pub struct RaidRankingsRequest<'c> {
+    client: &'c Client,
+    raid: String,
+    difficulty: Difficulty,
+    region: Option<Region>,
+    realm: Option<String>,
+    guilds: Option<String>,
+    max_pages: Option<u32>,
+    cache_mode: CacheMode,
+}
+
+impl<'c> RaidRankingsRequest<'c> {
+    /// Only rank the guilds of a realm, identified by its slug, requires a region
+    pub fn realm<S: Into<String>>(mut self, realm: S) -> Self {
+        self.realm = Some(realm.into());
+        self
+    }
+    /// Only rank some guilds, given as `realm-slug:guild name` pairs separated by `|`
+    pub fn guilds<S: Into<String>>(mut self, guilds: S) -> Self {
+        self.guilds = Some(guilds.into());
+        self
+    }
+    paged_request_setters!();
+
+    /// Guilds of the rankings, best first, fetching pages as the stream is polled
+    pub fn stream(&self) -> impl Stream<Item = Result<RaidRanking, Error>> + 'c {
+        let mut query = rankings_query(&self.raid, self.difficulty, self.region, &self.realm);
+        if let Some(guilds) = &self.guilds {
+            query.push(("guilds", guilds.clone()));
+        }
+        self.client.pages::<RaidRankingsPage>(
+            "raiding/raid-rankings",
+            query,
+            self.cache_mode,
+            self.max_pages,
+        )
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Encounter {
+    pub id: u64,
+    pub slug: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StaticRaid {
+    pub id: u64,
+    pub slug: String,
+    pub name: String,
+    pub short_name: String,
+    pub starts: RegionDates,
+    pub ends: RegionDates,
+    /// Bosses of the raid, in kill order
+    pub encounters: Vec<Encounter>,
+}
+
+/// Raids of an expansion
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StaticData {
+    pub raids: Vec<StaticRaid>,
+}
+impl StaticData {
+    pub fn raid_registry(&self) -> RaidRegistry {
+        RaidRegistry::new(self)
+    }
+}
+
+/// Lookup of raids and bosses, built from [`StaticData`](struct.StaticData.html)
+#[derive(Clone, Debug, Default)]
+pub struct RaidRegistry {
+    raids: Vec<StaticRaid>,
+}
+impl RaidRegistry {
+    pub fn new(data: &StaticData) -> RaidRegistry {
+        let mut registry = RaidRegistry::default();
+        registry.extend(data);
+        registry
+    }
+    /// Add the raids of another expansion
+    pub fn extend(&mut self, data: &StaticData) {
+        self.raids.extend(data.raids.iter().cloned());
+    }
+    pub fn raids(&self) -> &[StaticRaid] {
+        &self.raids
+    }
+    pub fn raid(&self, slug: &str) -> Option<&StaticRaid> {
+        self.raids.iter().find(|raid| raid.slug == slug)
+    }
+    /// Bosses of a raid, in kill order
+    pub fn bosses(&self, raid: &str) -> Option<&[Encounter]> {
+        self.raid(raid).map(|raid| raid.encounters.as_slice())
+    }
+    pub fn boss(&self, raid: &str, boss: &str) -> Option<&Encounter> {
+        self.bosses(raid)?
+            .iter()
+            .find(|encounter| encounter.slug == boss)
+    }
+    /// Display name of a raid
+    pub fn raid_name(&self, slug: &str) -> Option<&str> {
+        self.raid(slug).map(|raid| raid.name.as_str())
+    }
+    /// Display name of a boss
+    pub fn boss_name(&self, raid: &str, boss: &str) -> Option<&str> {
+        self.boss(raid, boss)
+            .map(|encounter| encounter.name.as_str())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BossRanking {
+    pub rank: u64,
+    pub region_rank: Option<u64>,
+    pub guild: RankedGuild,
+    pub defeated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub encounters_defeated: Vec<DefeatedEncounter>,
+    /// Slug of the ranked boss, for the rankings of a single boss, whose kill is `defeated_at`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boss: Option<String>,
+}
+impl BossRanking {
+    /// First kill of a boss, identified by its slug, by the guild
+    ///
+    /// The kill is looked up in the defeated encounters of the ranking, or is `defeated_at`
+    /// when the ranking is the one of this boss.
+    pub fn killed_at(&self, boss: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+        let encounter = self
+            .encounters_defeated
+            .iter()
+            .find(|encounter| encounter.slug == boss);
+        match encounter {
+            Some(encounter) => encounter.first_defeated,
+            None if self.boss.as_deref() == Some(boss) => self.defeated_at,
+            None => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BossRankingsPage {
+    boss_rankings: Vec<BossRanking>,
+}
+impl crate::Page for BossRankingsPage {
+    type Item = BossRanking;
+    fn into_items(self) -> Vec<BossRanking> {
+        self.boss_rankings
+    }
+}
+
+/// Builder for the rankings of guilds on a boss
+pub struct BossRankingsRequest<'c> {
+    client: &'c Client,
+    raid: String,
+    boss: String,
+    difficulty: Difficulty,
+    region: Option<Region>,
+    realm: Option<String>,
+    max_pages: Option<u32>,
+    cache_mode: CacheMode,
+}
+
+impl<'c> BossRankingsRequest<'c> {
+    /// Only rank the guilds of a realm, identified by its slug, requires a region
+    pub fn realm<S: Into<String>>(mut self, realm: S) -> Self {
+        self.realm = Some(realm.into());
+        self
+    }
+    paged_request_setters!();
+
+    /// Guilds of the rankings, first killers first, fetching pages as the stream is polled
+    pub fn stream(&self) -> impl Stream<Item = Result<BossRanking, Error>> + 'c {
+        let mut query = rankings_query(&self.raid, self.difficulty, self.region, &self.realm);
+        query.push(("boss", self.boss.clone()));
+        let boss = self.boss.clone();
+        self.client
+            .pages::<BossRankingsPage>(
+                "raiding/boss-rankings",
+                query,
+                self.cache_mode,
+                self.max_pages,
+            )
+            .map(move |ranking| {
+                ranking.map(|mut ranking| {
+                    ranking.boss = Some(boss.clone());
+                    ranking
+                })
+            })
+    }
+}
+
+/// First kills of a boss
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BossKills {
+    /// Slug of the boss
+    #[serde(deserialize_with = "player::deser_slug")]
+    pub boss: String,
+    /// Guilds that killed the boss, first killers first
+    #[serde(default)]
+    pub boss_rankings: Vec<BossRanking>,
+}
+
+/// Bosses defeated by a guild, with their dates
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GuildDefeats {
+    pub guild: RankedGuild,
+    #[serde(default)]
+    pub encounters_defeated: Vec<DefeatedEncounter>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HallOfFame {
+    /// First guilds to defeat the whole raid
+    #[serde(default)]
+    pub winners: Vec<BossRanking>,
+    #[serde(default)]
+    pub boss_kills: Vec<BossKills>,
+    /// Timeline of the defeats of each guild
+    #[serde(default)]
+    pub guild_defeats: Vec<GuildDefeats>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HallOfFameResponse {
+    hall_of_fame: HallOfFame,
+}
+
+/// Builder for the hall of fame of a raid
+pub struct HallOfFameRequest<'c> {
+    client: &'c Client,
+    raid: String,
+    difficulty: Difficulty,
+    region: Option<Region>,
+    cache_mode: CacheMode,
+}
+
+impl<'c> HallOfFameRequest<'c> {
+    /// Only consider the guilds of a region, guilds from the whole world are considered by
+    /// default
+    pub fn region(mut self, region: Region) -> Self {
+        self.region = Some(region);
+        self
+    }
+    /// Choose how this request uses the response cache of the client
+    pub fn cache_mode(mut self, mode: CacheMode) -> Self {
+        self.cache_mode = mode;
+        self
+    }
+
+    /// Execute the query on the raider.io website
+    pub async fn get(&self) -> Result<HallOfFame, Error> {
+        let query = [
+            ("raid", self.raid.as_str()),
+            ("difficulty", self.difficulty.text()),
+            ("region", crate::region_or_world(self.region)),
+        ];
+        let response: HallOfFameResponse = self
+            .client
+            .get("raiding/hall-of-fame", &query, self.cache_mode)
+            .await?;
+        let mut hall_of_fame = response.hall_of_fame;
+        for kills in &mut hall_of_fame.boss_kills {
+            for ranking in &mut kills.boss_rankings {
+                ranking.boss = Some(kills.boss.clone());
+            }
+        }
+        Ok(hall_of_fame)
+    }
+}
+
+/// Number of guilds having killed a boss at a point in time
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressPoint {
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub guilds: u64,
+}
+
+/// How many guilds killed a boss over time
+///
+/// Given by the API as
+/// `{"bossSlug": "wrathion", "totalGuilds": 200, "progress": [{"time": "...", "guilds": 10}]}`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BossProgression {
+    /// Slug of the boss
+    #[serde(rename = "bossSlug")]
+    pub boss: String,
+    /// Number of guilds progressing in the raid, the reference for the share of killers
+    pub total_guilds: Option<u64>,
+    /// Time series of the number of killers, in chronological order
+    pub progress: Vec<ProgressPoint>,
+}
+impl BossProgression {
+    /// Number of guilds that had killed the boss at `time`
+    pub fn guilds_at(&self, time: chrono::DateTime<chrono::Utc>) -> u64 {
+        self.progress
+            .iter()
+            .take_while(|point| point.time <= time)
+            .last()
+            .map_or(0, |point| point.guilds)
+    }
+    /// Share, between 0 and 1, of the guilds that had killed the boss at `time`
+    pub fn fraction_at(&self, time: chrono::DateTime<chrono::Utc>) -> Option<f64> {
+        match self.total_guilds {
+            Some(total) if total > 0 => Some(self.guilds_at(time) as f64 / total as f64),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ProgressionResponse {
+    progression: Vec<BossProgression>,
+}
+
+/// Builder for the progression of the guilds in a raid over time
+pub struct RaidProgressionOverTimeRequest<'c> {
+    client: &'c Client,
+    raid: String,
+    difficulty: Difficulty,
+    region: Option<Region>,
+    cache_mode: CacheMode,
+}
+
+impl<'c> RaidProgressionOverTimeRequest<'c> {
+    /// Only consider the guilds of a region, guilds from the whole world are considered by
+    /// default
+    pub fn region(mut self, region: Region) -> Self {
+        self.region = Some(region);
+        self
+    }
+    /// Choose how this request uses the response cache of the client
+    pub fn cache_mode(mut self, mode: CacheMode) -> Self {
+        self.cache_mode = mode;
+        self
+    }
+
+    /// Execute the query on the raider.io website
+    pub async fn get(&self) -> Result<Vec<BossProgression>, Error> {
+        let query = [
+            ("raid", self.raid.as_str()),
+            ("difficulty", self.difficulty.text()),
+            ("region", crate::region_or_world(self.region)),
+        ];
+        let response: ProgressionResponse = self
+            .client
+            .get("raiding/progression", &query, self.cache_mode)
+            .await?;
+        Ok(response.progression)
+    }
+}
+
+impl Client {
+    /// Builder for the number of guilds having killed each boss of a raid over time, the raid
+    /// being identified by its slug
+    pub fn raid_progression_over_time<S: Into<String>>(
+        &self,
+        raid: S,
+        difficulty: Difficulty,
+    ) -> RaidProgressionOverTimeRequest<'_> {
+        RaidProgressionOverTimeRequest {
+            client: self,
+            raid: raid.into(),
+            difficulty,
+            region: None,
+            cache_mode: CacheMode::default(),
+        }
+    }
+    /// Builder for the hall of fame of a raid, identified by its slug
+    pub fn raid_hall_of_fame<S: Into<String>>(
+        &self,
+        raid: S,
+        difficulty: Difficulty,
+    ) -> HallOfFameRequest<'_> {
+        HallOfFameRequest {
+            client: self,
+            raid: raid.into(),
+            difficulty,
+            region: None,
+            cache_mode: CacheMode::default(),
+        }
+    }
+    /// Builder for the rankings of guilds on a boss, the raid and the boss being identified by
+    /// their slugs
+    pub fn boss_rankings<R: Into<String>, B: Into<String>>(
+        &self,
+        raid: R,
+        boss: B,
+        difficulty: Difficulty,
+    ) -> BossRankingsRequest<'_> {
+        BossRankingsRequest {
+            client: self,
+            raid: raid.into(),
+            boss: boss.into(),
+            difficulty,
+            region: None,
+            realm: None,
+            max_pages: None,
+            cache_mode: CacheMode::default(),
+        }
+    }
+    /// Raids of an expansion
+    pub fn raid_static_data(&self, expansion: Expansion) -> Request<'_, StaticData> {
+        Request::new(
+            self,
+            "raiding/static-data",
+            vec![("expansion_id", expansion.id().to_string())],
+        )
+    }
+    /// Builder for the rankings of guilds in a raid, identified by its slug (for example
+    /// `nyalotha-the-waking-city`)
+    pub fn raid_rankings<S: Into<String>>(
+        &self,
+        raid: S,
+        difficulty: Difficulty,
+    ) -> RaidRankingsRequest<'_> {
+        RaidRankingsRequest {
+            client: self,
+            raid: raid.into(),
+            difficulty,
+            region: None,
+            realm: None,
+            guilds: None,
+            max_pages: None,
+            cache_mode: CacheMode::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[test]
+    fn raid_registry() {
+        let data: StaticData = serde_json::from_str(
+            r#"{"raids": [{
+                "id": 23798,
+                "slug": "castle-nathria",
+                "name": "Castle Nathria",
+                "short_name": "CN",
+                "starts": {"us": "2020-12-08T15:00:00Z", "eu": "2020-12-09T04:00:00Z", "tw": null, "kr": null},
+                "ends": {"us": null, "eu": null, "tw": null, "kr": null},
+                "encounters": [
+                    {"id": 2398, "slug": "shriekwing", "name": "Shriekwing"},
+                    {"id": 2418, "slug": "huntsman-altimor", "name": "Huntsman Altimor"}
+                ]
+            }]}"#,
+        )
+        .unwrap();
+        let registry = data.raid_registry();
+        assert_eq!(registry.raid_name("castle-nathria"), Some("Castle Nathria"));
+        let bosses: Vec<_> = registry
+            .bosses("castle-nathria")
+            .unwrap()
+            .iter()
+            .map(|boss| boss.slug.as_str())
+            .collect();
+        assert_eq!(bosses, ["shriekwing", "huntsman-altimor"]);
+        assert_eq!(
+            registry.boss_name("castle-nathria", "huntsman-altimor"),
+            Some("Huntsman Altimor")
+        );
+
+        let progression: RaidProgression = serde_json::from_str(
+            r#"{"castle-nathria": {
+                "summary": "2/10 M",
+                "total_bosses": 10,
+                "normal_bosses_killed": 10,
+                "heroic_bosses_killed": 10,
+                "mythic_bosses_killed": 2
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            progression
+                .get("castle-nathria")
+                .unwrap()
+                .mythic_bosses_killed,
+            2
+        );
+    }
+
+    #[test]
+    fn boss_ranking_kill_date() {
+        let ranking: BossRanking = serde_json::from_str(
+            r#"{
+                "rank": 3,
+                "guild": {
+                    "name": "Method",
+                    "faction": "horde",
+                    "realm": "Tarren Mill",
+                    "region": "eu"
+                },
+                "defeatedAt": "2020-01-30T23:00:00.000Z",
+                "encountersDefeated": [
+                    {"slug": "wrathion", "firstDefeated": "2020-01-22T20:00:00.000Z"},
+                    {"slug": "maut", "firstDefeated": "2020-01-23T21:00:00.000Z"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ranking.killed_at("maut").unwrap().to_rfc3339(),
+            "2020-01-23T21:00:00+00:00"
+        );
+        assert!(ranking.killed_at("nzoth-the-corruptor").is_none());
+
+        let mut ranking: BossRanking = serde_json::from_str(
+            r#"{
+                "rank": 1,
+                "guild": {"name": "Method", "faction": "horde", "realm": "Tarren Mill", "region": "eu"},
+                "defeatedAt": "2020-01-22T20:00:00.000Z"
+            }"#,
+        )
+        .unwrap();
+        assert!(ranking.killed_at("wrathion").is_none());
+        ranking.boss = Some("wrathion".to_owned());
+        assert_eq!(
+            ranking.killed_at("wrathion").unwrap().to_rfc3339(),
+            "2020-01-22T20:00:00+00:00"
+        );
+        assert!(ranking.killed_at("maut").is_none());
+    }
+
+    #[tokio::test]
+    async fn progression_over_time() {
+        let body = r#"{"progression": [{
+            "bossSlug": "wrathion",
+            "totalGuilds": 200,
+            "progress": [
+                {"time": "2020-01-22T00:00:00.000Z", "guilds": 10},
+                {"time": "2020-01-29T00:00:00.000Z", "guilds": 50}
+            ]
+        }]}"#;
+        let transport = crate::InMemoryTransport::new().respond(
+            "raiding/progression",
+            "raid=nyalotha-the-waking-city&difficulty=mythic&region=eu",
+            reqwest::StatusCode::OK,
+            body,
+        );
+        let client = Client::builder().transport(transport).build().unwrap();
+        let progression = client
+            .raid_progression_over_time("nyalotha-the-waking-city", Difficulty::Mythic)
+            .region(Region::Europe)
+            .get()
+            .await
+            .unwrap();
+        let wrathion = &progression[0];
+        assert_eq!(wrathion.boss, "wrathion");
+        let date = |date: &str| date.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        assert_eq!(wrathion.guilds_at(date("2020-01-20T00:00:00Z")), 0);
+        assert_eq!(wrathion.guilds_at(date("2020-01-25T00:00:00Z")), 10);
+        assert_eq!(
+            wrathion.fraction_at(date("2020-02-01T00:00:00Z")),
+            Some(0.25)
+        );
+    }
+
+    #[tokio::test]
+    async fn hall_of_fame() {
+        let body = r#"{"hallOfFame": {
+            "winners": [],
+            "bossKills": [{
+                "boss": {"slug": "wrathion", "name": "Wrathion"},
+                "bossRankings": [{
+                    "rank": 1,
+                    "guild": {"name": "Method", "faction": "horde", "realm": "Tarren Mill", "region": "eu"},
+                    "defeatedAt": "2020-01-22T20:00:00.000Z"
+                }]
+            }]
+        }}"#;
+        let transport = crate::InMemoryTransport::new().respond(
+            "raiding/hall-of-fame",
+            "raid=nyalotha-the-waking-city&difficulty=mythic&region=world",
+            reqwest::StatusCode::OK,
+            body,
+        );
+        let client = Client::builder().transport(transport).build().unwrap();
+        let hall_of_fame = client
+            .raid_hall_of_fame("nyalotha-the-waking-city", Difficulty::Mythic)
+            .get()
+            .await
+            .unwrap();
+        assert_eq!(hall_of_fame.boss_kills[0].boss, "wrathion");
+        let ranking = &hall_of_fame.boss_kills[0].boss_rankings[0];
+        assert_eq!(ranking.guild.name, "Method");
+        assert!(ranking.killed_at("wrathion").is_some());
+        assert!(ranking.killed_at("maut").is_none());
+    }
+
+    #[tokio::test]
+    async fn boss_rankings() {
+        let page = r#"{"bossRankings": [{
+            "rank": 1,
+            "guild": {"name": "Method", "faction": "horde", "realm": "Tarren Mill", "region": "eu"},
+            "defeatedAt": "2020-01-22T20:00:00.000Z"
+        }]}"#;
+        let query = "raid=nyalotha-the-waking-city&boss=wrathion&difficulty=mythic&region=eu&realm=tarren-mill";
+        let client = crate::paged_client(
+            "raiding/boss-rankings",
+            query,
+            page,
+            r#"{"bossRankings": []}"#,
+        );
+        let rankings: Vec<_> = client
+            .boss_rankings("nyalotha-the-waking-city", "wrathion", Difficulty::Mythic)
+            .region(Region::Europe)
+            .realm("tarren-mill")
+            .stream()
+            .collect()
+            .await;
+        assert_eq!(rankings.len(), 1);
+        let ranking = rankings[0].as_ref().unwrap();
+        assert!(ranking.killed_at("wrathion").is_some());
+        assert!(ranking.killed_at("maut").is_none());
+    }
+
+    #[tokio::test]
+    async fn raid_rankings() {
+        let page = r#"{"raidRankings": [{
+            "rank": 1,
+            "regionRank": 1,
+            "guild": {
+                "id": 1,
+                "name": "Method",
+                "faction": "horde",
+                "realm": {"id": 1, "name": "Tarren Mill", "slug": "tarren-mill"},
+                "region": {"name": "Europe", "slug": "eu", "short_name": "EU"},
+                "path": "/guilds/eu/tarren-mill/Method"
+            },
+            "encountersDefeated": [
+                {"slug": "wrathion", "firstDefeated": "2020-01-22T20:00:00.000Z"}
+            ]
+        }]}"#;
+        let query = "raid=nyalotha-the-waking-city&difficulty=mythic&region=eu";
+        let client = crate::paged_client(
+            "raiding/raid-rankings",
+            query,
+            page,
+            r#"{"raidRankings": []}"#,
+        );
+        let rankings: Vec<_> = client
+            .raid_rankings("nyalotha-the-waking-city", Difficulty::Mythic)
+            .region(Region::Europe)
+            .stream()
+            .collect()
+            .await;
+        assert_eq!(rankings.len(), 1);
+        let ranking = rankings[0].as_ref().unwrap();
+        assert_eq!(ranking.guild.region, Region::Europe);
+        assert_eq!(ranking.guild.realm, "Tarren Mill");
+    }
 }
@@ -0,0 +1,114 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket limiting the number of requests sent to the API
+///
+/// Requests waiting for a token are served in the order they asked for it.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    per_second: f64,
+    bucket: Mutex<Bucket>,
+    queue: tokio::sync::Mutex<()>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Snapshot of the requests a [`RateLimiter`](struct.RateLimiter.html) allows right now
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Budget {
+    /// Requests that can be sent without waiting
+    pub available: u32,
+    /// Maximum number of requests that can be sent in a burst
+    pub capacity: u32,
+    /// Time until the next request is available, zero if one already is
+    pub next_in: Duration,
+}
+
+impl RateLimiter {
+    /// Allow `requests` per minute, with bursts of up to `requests`
+    ///
+    /// A limit of 0 would never let a request through, so it is raised to 1.
+    pub fn per_minute(requests: u32) -> RateLimiter {
+        let requests = requests.max(1);
+        RateLimiter {
+            capacity: requests as f64,
+            per_second: requests as f64 / 60.,
+            bucket: Mutex::new(Bucket {
+                tokens: requests as f64,
+                last_refill: Instant::now(),
+            }),
+            queue: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.capacity);
+        bucket.last_refill = now;
+    }
+
+    fn wait_time(&self, bucket: &Bucket) -> Duration {
+        if bucket.tokens >= 1. {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64((1. - bucket.tokens) / self.per_second)
+        }
+    }
+
+    /// Wait until a request can be sent, and consume it from the budget
+    pub async fn acquire(&self) {
+        let _turn = self.queue.lock().await;
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                self.refill(&mut bucket);
+                if bucket.tokens >= 1. {
+                    bucket.tokens -= 1.;
+                    return;
+                }
+                self.wait_time(&bucket)
+            };
+            tokio::time::delay_for(wait).await;
+        }
+    }
+
+    /// Current state of the budget
+    pub fn budget(&self) -> Budget {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        Budget {
+            available: bucket.tokens.floor() as u32,
+            capacity: self.capacity as u32,
+            next_in: self.wait_time(&bucket),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn consumes_budget() {
+        let limiter = RateLimiter::per_minute(2);
+        limiter.acquire().await;
+        limiter.acquire().await;
+        let budget = limiter.budget();
+        assert_eq!(budget.available, 0);
+        assert_eq!(budget.capacity, 2);
+        assert!(budget.next_in > Duration::from_secs(25));
+    }
+
+    #[test]
+    fn zero_limit_allows_one_request() {
+        let budget = RateLimiter::per_minute(0).budget();
+        assert_eq!(budget.available, 1);
+        assert_eq!(budget.capacity, 1);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::Error;
+use rand::Rng;
+use std::time::Duration;
+
+/// How a [`Client`](../struct.Client.html) retries requests that failed transiently
+///
+/// Rate limited requests, server errors, timeouts and connection failures are retried.
+/// The delay between two attempts doubles each time, up to `max_delay`, unless the server
+/// asked for a specific delay with a `Retry-After` header. Requests are not retried when the
+/// server asks to wait longer than `max_delay`.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
+
+impl RetryPolicy {
+    /// Try each request at most `max_attempts` times, including the first one
+    pub fn new(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+    /// Delay before the first retry, defaults to 500ms
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+    /// Upper bound of the exponential backoff and of the delays asked by the server, defaults
+    /// to 30s
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+    /// Randomize up to half of each delay, enabled by default
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay before retrying after the `attempt`-th try failed with `error`, or `None` if the
+    /// request should not be retried
+    pub(crate) fn delay(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts || !error.is_transient() {
+            return None;
+        }
+        if let Some(retry_after) = error.retry_after() {
+            return Some(retry_after).filter(|delay| *delay <= self.max_delay);
+        }
+        let exponent = (attempt - 1).min(31);
+        let backoff = self
+            .base_delay
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        if self.jitter {
+            let half = backoff / 2;
+            Some(
+                half + rand::thread_rng()
+                    .gen_range(Duration::from_secs(0), half + Duration::from_nanos(1)),
+            )
+        } else {
+            Some(backoff)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RetryPolicy::new(5)
+            .base_delay(Duration::from_secs(1))
+            .max_delay(Duration::from_secs(3))
+            .jitter(false);
+        let error = Error::Server {
+            status: 502,
+            body: String::new(),
+            retry_after: None,
+        };
+        assert_eq!(policy.delay(1, &error), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay(2, &error), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay(3, &error), Some(Duration::from_secs(3)));
+        assert_eq!(policy.delay(5, &error), None);
+
+        let retry_after = Error::RateLimited {
+            retry_after: Some(Duration::from_secs(2)),
+        };
+        assert_eq!(policy.delay(1, &retry_after), Some(Duration::from_secs(2)));
+        let retry_after = Error::RateLimited {
+            retry_after: Some(Duration::from_secs(10)),
+        };
+        assert_eq!(policy.delay(1, &retry_after), None);
+        let unavailable = Error::Server {
+            status: 503,
+            body: String::new(),
+            retry_after: Some(Duration::from_millis(2500)),
+        };
+        assert_eq!(
+            policy.delay(1, &unavailable),
+            Some(Duration::from_millis(2500))
+        );
+
+        let not_found = Error::CharacterNotFound {
+            message: String::new(),
+        };
+        assert_eq!(policy.delay(1, &not_found), None);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::Error;
+use async_trait::async_trait;
+use reqwest::{header::HeaderMap, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Raw response returned by a [`Transport`](trait.Transport.html)
+#[derive(Clone, Debug)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// Sends the http requests of a [`Client`](../struct.Client.html)
+#[async_trait]
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    /// Send a GET request to `url`, with the already encoded `query` string
+    async fn get(&self, url: &str, query: &str) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn get(&self, url: &str, query: &str) -> Result<Response, Error> {
+        (**self).get(url, query).await
+    }
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    async fn get(&self, url: &str, query: &str) -> Result<Response, Error> {
+        (**self).get(url, query).await
+    }
+}
+
+/// Transport sending requests over the network with reqwest
+#[derive(Clone, Debug)]
+pub struct ReqwestTransport {
+    http_client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(http_client: reqwest::Client) -> ReqwestTransport {
+        ReqwestTransport { http_client }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn get(&self, url: &str, query: &str) -> Result<Response, Error> {
+        let url = if query.is_empty() {
+            url.to_owned()
+        } else {
+            format!("{}?{}", url, query)
+        };
+        let response = self.http_client.get(&url).send().await?;
+        Ok(Response {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.text().await?,
+        })
+    }
+}
+
+/// Transport answering with canned responses, without any network access
+///
+/// Responses are matched on the path of the endpoint (for example `characters/profile`), which
+/// must end the url on whole path segments, and on the query, regardless of the order of its
+/// parameters. Requests without a canned response get a 404 error.
+#[derive(Debug, Default)]
+pub struct InMemoryTransport {
+    responses: HashMap<String, Response>,
+    requests: Mutex<Vec<String>>,
+}
+
+impl InMemoryTransport {
+    pub fn new() -> InMemoryTransport {
+        Default::default()
+    }
+    /// Answer requests to `path` with `query` with `body`, using the status `status`
+    pub fn respond<B: Into<String>>(
+        mut self,
+        path: &str,
+        query: &str,
+        status: StatusCode,
+        body: B,
+    ) -> Self {
+        self.responses.insert(
+            crate::cache::key(path.trim_matches('/'), query),
+            Response {
+                status,
+                headers: HeaderMap::new(),
+                body: body.into(),
+            },
+        );
+        self
+    }
+    /// Urls of the requests received so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for InMemoryTransport {
+    async fn get(&self, url: &str, query: &str) -> Result<Response, Error> {
+        self.requests
+            .lock()
+            .unwrap()
+            .push(format!("{}?{}", url, query));
+        // The canned path is a suffix of the url made of whole segments, the longest one wins
+        let url = url.trim_end_matches('/');
+        let paths = std::iter::once(url).chain(url.match_indices('/').map(|(i, _)| &url[i + 1..]));
+        let response = paths
+            .filter_map(|path| self.responses.get(&crate::cache::key(path, query)))
+            .next()
+            .cloned();
+        Ok(response.unwrap_or_else(|| Response {
+            status: StatusCode::NOT_FOUND,
+            headers: HeaderMap::new(),
+            body: serde_json::json!({
+                "statusCode": 404,
+                "error": "Not Found",
+                "message": format!("no canned response for {}?{}", url, query),
+            })
+            .to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn match_whole_path_segments() {
+        let transport = InMemoryTransport::new()
+            .respond(
+                "characters/profile",
+                "name=a&region=eu",
+                StatusCode::OK,
+                "character",
+            )
+            .respond("file", "", StatusCode::OK, "file");
+        let response = transport
+            .get(
+                "https://raider.io/api/v1/characters/profile",
+                "region=eu&name=a",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.body, "character");
+        let response = transport
+            .get("https://raider.io/api/v1/characters/profile", "")
+            .await
+            .unwrap();
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        let response = transport
+            .get("https://raider.io/api/v1/guilds/profile", "")
+            .await
+            .unwrap();
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+    }
+}